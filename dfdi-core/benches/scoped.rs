@@ -0,0 +1,92 @@
+use std::{any::TypeId, collections::HashMap};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use dfdi::{Context, Service};
+
+/// A service type that can be instantiated many times, each with a distinct `TypeId`
+struct Svc<const A: u8, const B: u8>;
+
+impl<const A: u8, const B: u8> Service for Svc<A, B> {
+    type Output<'cx> = u16;
+    type Argument<'arg> = ();
+}
+
+macro_rules! bind_row {
+    ($cx:expr, $ids:expr, $a:literal; $($b:literal)*) => {
+        $(
+            $cx.bind_fn::<Svc<$a, $b>>(|_cx, _arg| $a * 16 + $b);
+            $ids.push(TypeId::of::<Svc<$a, $b>>());
+        )*
+    };
+}
+
+macro_rules! bind_rows {
+    ($cx:expr, $ids:expr; $($a:literal)*) => {
+        $( bind_row!($cx, $ids, $a; 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15); )*
+    };
+}
+
+/// Create a context with `rows * 16` bound services, along with their `TypeId`s
+fn context(rows: u8) -> (Context<'static>, Vec<TypeId>) {
+    let mut cx = Context::new();
+    let mut ids = Vec::new();
+    match rows {
+        1 => {
+            bind_rows!(cx, ids; 0);
+        }
+        4 => {
+            bind_rows!(cx, ids; 0 1 2 3);
+        }
+        16 => {
+            bind_rows!(cx, ids; 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15);
+        }
+        _ => unreachable!(),
+    }
+    (cx, ids)
+}
+
+fn scoped(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scoped");
+
+    for rows in [1, 4, 16] {
+        let (cx, ids) = context(rows);
+        let services = ids.len();
+
+        group.bench_with_input(BenchmarkId::new("chained", services), &cx, |b, cx| {
+            b.iter(|| black_box(cx.scoped()))
+        });
+
+        // The previous design cloned the parent's provider map into every sub-context. This map
+        // has the same keys, with values the size of the old `DynProvider`.
+        let map: HashMap<TypeId, [usize; 3]> = ids.into_iter().map(|id| (id, [0; 3])).collect();
+        group.bench_with_input(BenchmarkId::new("cloned", services), &map, |b, map| {
+            b.iter(|| black_box(map.clone()))
+        });
+    }
+
+    group.finish();
+}
+
+fn resolve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resolve");
+
+    let (cx, _) = context(16);
+    group.bench_function("root", |b| b.iter(|| black_box(cx.resolve::<Svc<7, 7>>())));
+
+    let scope = cx.scoped();
+    group.bench_function("scope", |b| {
+        b.iter(|| black_box(scope.resolve::<Svc<7, 7>>()))
+    });
+
+    let nested = scope.scoped();
+    let nested = nested.scoped();
+    let nested = nested.scoped();
+    group.bench_function("nested_scope", |b| {
+        b.iter(|| black_box(nested.resolve::<Svc<7, 7>>()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, scoped, resolve);
+criterion_main!(benches);
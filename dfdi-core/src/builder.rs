@@ -0,0 +1,211 @@
+use std::marker::PhantomData;
+
+use crate::{context::Key, BindError, Context, Provider, Service};
+
+/// The empty set of services
+pub struct HNil;
+
+/// The set of services made of the service `H` and the services in the set `T`
+pub struct HCons<H, T>(PhantomData<fn() -> (H, T)>);
+
+/// The type of a set of services, to be used with [`ContextBuilder`]
+///
+/// `HList![A, B, C]` expands to `HCons<A, HCons<B, HCons<C, HNil>>>`.
+#[macro_export]
+macro_rules! HList {
+    () => { $crate::HNil };
+    ($head:ty $(, $tail:ty)* $(,)?) => { $crate::HCons<$head, $crate::HList![$($tail),*]> };
+}
+
+/// The index of a service at the head of a set of services
+pub struct Here;
+
+/// The index of a service in the tail of a set of services, at the index `I`
+pub struct There<I>(PhantomData<fn() -> I>);
+
+/// A set of services containing the service `S`, at the index `I`
+///
+/// The index is always inferred, and only exists so that the implementations for the head and the
+/// tail of a set do not overlap.
+pub trait Contains<S, I> {}
+
+impl<S, T> Contains<S, Here> for HCons<S, T> {}
+
+impl<S, H, T, I> Contains<S, There<I>> for HCons<H, T> where T: Contains<S, I> {}
+
+/// A builder for a [`Context`] which keeps track of the services bound to it in its type
+///
+/// Services bound through the builder can be resolved from it without any chance of them being
+/// unbound. Once every service is bound, [`build`](Self::build) produces a regular `Context`.
+///
+/// Each binding adds its service to the head of the set `L`:
+/// ```
+/// # use dfdi::{Context, ContextBuilder, HList, Service};
+/// #[derive(Service)]
+/// struct Config(u32);
+///
+/// #[derive(Service)]
+/// struct Server(u32);
+///
+/// let builder: ContextBuilder<HList![Server, Config]> = ContextBuilder::new()
+///     .bind_fn::<Config>(|_cx, _arg| Config(8080))
+///     .bind_fn::<Server>(|cx, _arg| Server(cx.resolve::<Config>().0));
+///
+/// assert_eq!(builder.resolve::<Server, _>().0, 8080);
+///
+/// let cx: Context = builder.build();
+/// assert_eq!(cx.resolve::<Server>().0, 8080);
+/// ```
+///
+/// Resolving a service that was not bound through the builder does not compile:
+/// ```compile_fail
+/// # use dfdi::{ContextBuilder, Service};
+/// #[derive(Service)]
+/// struct Config(u32);
+///
+/// #[derive(Service)]
+/// struct Server(u32);
+///
+/// let builder = ContextBuilder::new().bind_fn::<Config>(|_cx, _arg| Config(8080));
+/// builder.resolve::<Server, _>();
+/// ```
+///
+/// Since the builder owns its context, its providers can not borrow from it for any particular
+/// lifetime. They must implement [`Provider`] for every lifetime instead.
+pub struct ContextBuilder<L> {
+    cx: Context<'static>,
+    services: PhantomData<fn() -> L>,
+}
+
+impl ContextBuilder<HNil> {
+    /// Create a builder for an empty context
+    pub fn new() -> Self {
+        Self {
+            cx: Context::new(),
+            services: PhantomData,
+        }
+    }
+}
+
+impl<L> ContextBuilder<L> {
+    /// Register a new provider for the service `S`
+    ///
+    /// # Panics
+    /// If the service binding fails. See [`try_bind_with`](Self::try_bind_with) for a fallible
+    /// version of this function.
+    #[track_caller]
+    pub fn bind_with<S, P>(self, provider: P) -> ContextBuilder<HCons<S, L>>
+    where
+        S: Service,
+        P: for<'cx> Provider<'cx, S>,
+    {
+        match self.try_bind_with::<S, P>(provider) {
+            Ok(builder) => builder,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Register a function as a provider for the service `S`
+    ///
+    /// # Panics
+    /// If the service binding fails. See [`try_bind_with`](Self::try_bind_with) for a fallible
+    /// version of this function.
+    #[track_caller]
+    pub fn bind_fn<S>(
+        self,
+        provider_fn: impl for<'cx> Fn(&'cx Context, S::Argument<'_>) -> S::Output<'cx>
+            + Send
+            + Sync
+            + 'static,
+    ) -> ContextBuilder<HCons<S, L>>
+    where
+        S: Service,
+    {
+        self.bind_with::<S, _>(provider_fn)
+    }
+
+    /// Bind the provider `P` to the service `S`
+    ///
+    /// # Panics
+    /// If the service binding fails. See [`try_bind_with`](Self::try_bind_with) for a fallible
+    /// version of this function.
+    #[track_caller]
+    pub fn bind<S, P>(self) -> ContextBuilder<HCons<S, L>>
+    where
+        S: Service,
+        P: for<'cx> Provider<'cx, S> + Default,
+    {
+        self.bind_with::<S, P>(P::default())
+    }
+
+    /// Try to register a new provider for the service `S`
+    ///
+    /// # Fails
+    /// This function will fail if a provider is already bound to the service.
+    ///
+    /// See [`bind_with`](Self::bind_with) for the panicking version of this function.
+    #[track_caller]
+    pub fn try_bind_with<S, P>(
+        mut self,
+        provider: P,
+    ) -> Result<ContextBuilder<HCons<S, L>>, BindError>
+    where
+        S: Service,
+        P: for<'cx> Provider<'cx, S>,
+    {
+        // Note: The provider does not borrow from the context for any particular lifetime, so it
+        // can be bound without borrowing the context either
+        self.cx.bind_static::<S, P>(Key::of::<S>(None), provider)?;
+
+        Ok(ContextBuilder {
+            cx: self.cx,
+            services: PhantomData,
+        })
+    }
+
+    /// Resolve the service `S` using the default service argument.
+    ///
+    /// This only compiles if `S` was bound through the builder. The index `I` is always inferred.
+    ///
+    /// # Panics
+    /// If the provider fails. See [`Context::resolve`].
+    #[inline(always)]
+    #[track_caller]
+    pub fn resolve<S, I>(&self) -> S::Output<'_>
+    where
+        S: Service,
+        S::Argument<'static>: Default,
+        L: Contains<S, I>,
+    {
+        self.cx.resolve::<S>()
+    }
+
+    /// Resolve the service `S` given the service argument.
+    ///
+    /// This only compiles if `S` was bound through the builder. The index `I` is always inferred.
+    ///
+    /// # Panics
+    /// If the provider fails. See [`Context::resolve_with`].
+    #[inline(always)]
+    #[track_caller]
+    pub fn resolve_with<S, I>(&self, arg: S::Argument<'_>) -> S::Output<'_>
+    where
+        S: Service,
+        L: Contains<S, I>,
+    {
+        self.cx.resolve_with::<S>(arg)
+    }
+
+    /// Get the context with all of the services bound so far
+    #[inline(always)]
+    pub fn build(self) -> Context<'static> {
+        self.cx
+    }
+}
+
+impl Default for ContextBuilder<HNil> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,24 +1,61 @@
 use std::{
     any::{type_name, TypeId},
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::Debug,
+    future::Future,
     marker::PhantomData,
+    panic::Location,
     ptr::NonNull,
+    sync::{Mutex, PoisonError},
 };
 
-use crate::{BindError, ProvideFn, Provider, Service, UnbindError};
+use once_cell::sync::OnceCell;
+
+use crate::{
+    impls::{Upcasted, UpcastedWith},
+    lifetime::{Disposer, Instances, WithLifetime},
+    stack::{self, Frame, StackGuard},
+    AsyncProvider, BindError, BoxFuture, Dependency, DependencyGraph, Dispose, Lifetime, Module,
+    ProvideAsyncFn, ProvideFn, Provider, ResolveError, Service, ServiceNode, ShutdownError,
+    UnbindError, Upcast, ValidationError,
+};
 
 /// A context in which to store providers for services
 pub struct Context<'pcx> {
-    /// Map `Service` `TypeId`s to a type-erased provider
+    /// The instances created in this context by providers bound with a [`Lifetime`]
+    //
+    // Note: This must be the first field, so that instances are dropped before the providers they
+    // may borrow from.
+    instances: Instances,
+
+    /// Map services (and their optional names) to a type-erased provider
+    ///
+    /// A `None` entry marks a service that was unbound in this context, and hides any provider
+    /// bound to it by a parent context.
     //
     // Note: Unfortunately, https://github.com/rust-lang/rust/issues/10389 is an I-unsound bug to
     // keep an eye on. TL;DR: TypeId hash collisions are possible and there have been some (obscure)
     // examples of this in the past.
-    providers: HashMap<TypeId, DynProvider>,
+    providers: HashMap<Key, Option<DynProvider>>,
+
+    /// The context this context was created from. Services not found in `providers` are looked up
+    /// here instead.
+    ///
+    /// The reference also ensures that this context does not outlive its parent, which owns the
+    /// providers we resolve through it.
+    parent: Option<&'pcx Context<'pcx>>,
+
+    /// Map `Service` `TypeId`s to all the providers contributed to them, in registration order
+    multi_providers: HashMap<TypeId, Vec<DynProvider>>,
 
-    /// Ensure that this context does not outlive its parent. This is required since we only want to
-    /// drop providers once, on the parent scope.
-    _phantom: PhantomData<&'pcx ()>,
+    /// The services resolved through this context and its sub-contexts, and their dependencies
+    ///
+    /// Only root contexts have a graph, so that it outlives the scopes that record into it.
+    graph: Option<Mutex<DependencyGraph>>,
+
+    /// The `TypeId`s of the modules installed in this context
+    modules: HashSet<TypeId>,
 }
 
 // SAFETY:
@@ -33,8 +70,12 @@ impl Context<'_> {
     /// Create an empty context
     pub fn new() -> Self {
         Self {
+            instances: Instances::default(),
             providers: HashMap::new(),
-            _phantom: PhantomData,
+            parent: None,
+            multi_providers: HashMap::new(),
+            graph: Some(Mutex::default()),
+            modules: HashSet::new(),
         }
     }
 
@@ -43,14 +84,34 @@ impl Context<'_> {
     /// The retuned context will contain the same elements as the parent context and any elements
     /// added to the sub context will not be visible on the original. However, the underlying
     /// providers that were added before this call are shared between the two contexts.
+    ///
+    /// This is a cheap operation: The sub-context only stores its own bindings and falls back to
+    /// the parent context for everything else.
+    ///
+    /// ```
+    /// # use dfdi::{Context, Service};
+    /// #[derive(Service)]
+    /// struct Answer(u32);
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_fn::<Answer>(|_cx, _arg| Answer(42));
+    ///
+    /// let mut scope = cx.scoped();
+    /// assert_eq!(scope.resolve::<Answer>().0, 42);
+    ///
+    /// // Unbinding a service in the sub-context hides it there, but not in the parent
+    /// scope.unbind::<Answer>();
+    /// assert!(scope.try_resolve::<Answer>().is_err());
+    /// assert_eq!(cx.resolve::<Answer>().0, 42);
+    /// ```
     pub fn scoped(&self) -> Context<'_> {
-        // Notes:
-        // - We are cloning the pointers, not the underlying data
-        // - Provider expects a shared reference
-        // - DynProvider's clone implementation skips the drop function for clones
         Context {
-            providers: self.providers.clone(),
-            _phantom: PhantomData,
+            instances: Instances::default(),
+            providers: HashMap::new(),
+            parent: Some(self),
+            multi_providers: HashMap::new(),
+            graph: None,
+            modules: HashSet::new(),
         }
     }
 
@@ -97,6 +158,365 @@ impl Context<'_> {
         }
     }
 
+    /// Bind the trait object service `T` to a default instance of the concrete type `C`
+    ///
+    /// The instance is created right away, and shared by every resolution of `&T`.
+    ///
+    /// ```
+    /// # use dfdi::{service_trait, Context};
+    /// #[service_trait]
+    /// trait Storage {
+    ///     fn name(&self) -> &str;
+    /// }
+    ///
+    /// #[derive(Default)]
+    /// struct LocalStorage;
+    ///
+    /// impl Storage for LocalStorage {
+    ///     fn name(&self) -> &str {
+    ///         "local"
+    ///     }
+    /// }
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_as::<dyn Storage, LocalStorage>();
+    ///
+    /// assert_eq!(cx.resolve::<&dyn Storage>().name(), "local");
+    /// ```
+    ///
+    /// # Panics
+    /// If the service binding fails. See [`try_bind_as`](Self::try_bind_as) for a fallible version
+    /// of this function.
+    #[track_caller]
+    pub fn bind_as<'cx, T, C>(&'cx mut self)
+    where
+        T: Upcast<C> + ?Sized,
+        T::Output<'cx>: Send + Sync,
+        C: Default + 'cx,
+    {
+        if let Err(err) = self.try_bind_as::<T, C>() {
+            panic!("{}", err)
+        }
+    }
+
+    /// Bind the trait object service `T` to the output of a provider of the service `C`
+    ///
+    /// Unlike [`bind_as`](Self::bind_as), the concrete instance can be built from configuration or
+    /// from other services. The provider is run with the default argument the first time `&T` is
+    /// resolved, and its output is shared by every later resolution.
+    ///
+    /// ```
+    /// # use dfdi::{provider_fn, service_trait, Context, Service};
+    /// #[service_trait]
+    /// trait Storage {
+    ///     fn location(&self) -> String;
+    /// }
+    ///
+    /// #[derive(Service)]
+    /// struct Config {
+    ///     bucket: Option<&'static str>,
+    /// }
+    ///
+    /// #[derive(Service)]
+    /// struct S3Storage(&'static str);
+    ///
+    /// impl Storage for S3Storage {
+    ///     fn location(&self) -> String {
+    ///         format!("s3://{}", self.0)
+    ///     }
+    /// }
+    ///
+    /// #[derive(Service)]
+    /// struct LocalStorage;
+    ///
+    /// impl Storage for LocalStorage {
+    ///     fn location(&self) -> String {
+    ///         "/var/lib/app".to_string()
+    ///     }
+    /// }
+    ///
+    /// static CONFIG: Config = Config { bucket: Some("assets") };
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_fn::<&Config>(|_cx, _arg| &CONFIG);
+    /// if CONFIG.bucket.is_some() {
+    ///     cx.bind_as_with::<dyn Storage, S3Storage>(provider_fn::<S3Storage>(|cx, _arg| {
+    ///         S3Storage(cx.resolve::<&Config>().bucket.unwrap())
+    ///     }));
+    /// } else {
+    ///     cx.bind_as_with::<dyn Storage, LocalStorage>(provider_fn::<LocalStorage>(|_cx, _arg| {
+    ///         LocalStorage
+    ///     }));
+    /// }
+    ///
+    /// assert_eq!(cx.resolve::<&dyn Storage>().location(), "s3://assets");
+    /// ```
+    ///
+    /// The provider is always run with the context `&T` is bound in, even when `&T` is first
+    /// resolved from a sub-context, so the output does not borrow anything the sub-context owns:
+    /// ```
+    /// # use dfdi::{provider_fn, service_trait, Context, Lifetime, Service};
+    /// #[service_trait]
+    /// trait Storage {
+    ///     fn bucket(&self) -> &str;
+    /// }
+    ///
+    /// #[derive(Service)]
+    /// struct Config(String);
+    ///
+    /// #[derive(Service)]
+    /// struct S3Storage<'cx>(&'cx Config);
+    ///
+    /// impl Storage for S3Storage<'_> {
+    ///     fn bucket(&self) -> &str {
+    ///         &self.0 .0
+    ///     }
+    /// }
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_with_lifetime(
+    ///     Lifetime::Scoped,
+    ///     provider_fn::<Config>(|_cx, _arg| Config("assets".to_string())),
+    /// );
+    /// cx.bind_as_with::<dyn Storage, S3Storage>(provider_fn::<S3Storage>(|cx, _arg| {
+    ///     S3Storage(cx.resolve::<&Config>())
+    /// }));
+    ///
+    /// let scope = cx.scoped();
+    /// assert_eq!(scope.resolve::<&dyn Storage>().bucket(), "assets");
+    /// drop(scope);
+    ///
+    /// assert_eq!(cx.resolve::<&dyn Storage>().bucket(), "assets");
+    /// ```
+    ///
+    /// # Panics
+    /// If the service binding fails. See [`try_bind_as_with`](Self::try_bind_as_with) for a
+    /// fallible version of this function.
+    #[track_caller]
+    pub fn bind_as_with<'cx, T, C>(&'cx mut self, provider: impl Provider<'cx, C>)
+    where
+        T: Upcast<C::Output<'cx>> + ?Sized,
+        T::Output<'cx>: Send + Sync,
+        C: Service + ?Sized,
+        C::Output<'cx>: 'cx,
+        C::Argument<'static>: Default,
+    {
+        if let Err(err) = self.try_bind_as_with::<T, C>(provider) {
+            panic!("{}", err)
+        }
+    }
+
+    /// Register a new provider for the service `S`, whose instances are shared according to the
+    /// given [`Lifetime`]
+    ///
+    /// Singleton and scoped instances are kept by a context, and resolved by reference through the
+    /// service `&S`.
+    ///
+    /// ```
+    /// # use dfdi::{provider_fn, Context, Lifetime, Service};
+    /// # use std::sync::atomic::{AtomicU32, Ordering};
+    /// #[derive(Service)]
+    /// struct Transaction(u32);
+    ///
+    /// static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_with_lifetime(
+    ///     Lifetime::Scoped,
+    ///     provider_fn::<Transaction>(|_cx, _arg| Transaction(NEXT_ID.fetch_add(1, Ordering::SeqCst))),
+    /// );
+    ///
+    /// // Each scope gets its own transaction, which is shared by all resolutions in that scope
+    /// let first = cx.scoped();
+    /// let second = cx.scoped();
+    /// assert_eq!(first.resolve::<&Transaction>().0, first.resolve::<&Transaction>().0);
+    /// assert_ne!(first.resolve::<&Transaction>().0, second.resolve::<&Transaction>().0);
+    /// ```
+    ///
+    /// Transient instances are handed out by value through the service `S` itself, so they are
+    /// released as soon as they are dropped:
+    /// ```
+    /// # use dfdi::{provider_fn, Context, Lifetime, Service};
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// static DROPPED: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// #[derive(Service)]
+    /// struct Buffer;
+    ///
+    /// impl Drop for Buffer {
+    ///     fn drop(&mut self) {
+    ///         DROPPED.fetch_add(1, Ordering::SeqCst);
+    ///     }
+    /// }
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_with_lifetime(Lifetime::Transient, provider_fn::<Buffer>(|_cx, _arg| Buffer));
+    ///
+    /// for _ in 0..1000 {
+    ///     cx.resolve::<Buffer>();
+    /// }
+    /// assert_eq!(DROPPED.load(Ordering::SeqCst), 1000);
+    /// assert!(cx.try_resolve::<&Buffer>().is_err());
+    /// ```
+    ///
+    /// # Panics
+    /// If the service binding fails. See [`try_bind_with_lifetime`](Self::try_bind_with_lifetime)
+    /// for a fallible version of this function.
+    #[track_caller]
+    pub fn bind_with_lifetime<'cx, S>(
+        &'cx mut self,
+        lifetime: Lifetime,
+        provider: impl Provider<'cx, S>,
+    ) where
+        S: Service + ?Sized,
+        S::Output<'cx>: Send + Sync,
+    {
+        if let Err(err) = self.try_bind_with_lifetime::<S>(lifetime, provider) {
+            panic!("{}", err)
+        }
+    }
+
+    /// Register a new provider for the service `S`, whose instances are shared according to the
+    /// given [`Lifetime`] and disposed of by the context that owns them
+    ///
+    /// Instances are disposed of in the reverse order they were created in, either when the
+    /// context is dropped or when calling [`shutdown`](Self::shutdown).
+    ///
+    /// ```
+    /// # use dfdi::{provider_fn, Context, Dispose, Lifetime, Service};
+    /// # use std::{error::Error, sync::Mutex};
+    /// static LOG: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+    ///
+    /// #[derive(Service)]
+    /// struct Pool;
+    ///
+    /// impl Dispose for Pool {
+    ///     fn dispose(&mut self) -> Result<(), Box<dyn Error + Send + Sync>> {
+    ///         LOG.lock().unwrap().push("pool flushed");
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// #[derive(Service)]
+    /// struct Journal;
+    ///
+    /// impl Dispose for Journal {
+    ///     fn dispose(&mut self) -> Result<(), Box<dyn Error + Send + Sync>> {
+    ///         LOG.lock().unwrap().push("journal synced");
+    ///         Err("disk full".into())
+    ///     }
+    /// }
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_disposable(Lifetime::Singleton, provider_fn::<Pool>(|_cx, _arg| Pool));
+    /// cx.bind_disposable(
+    ///     Lifetime::Singleton,
+    ///     provider_fn::<Journal>(|cx, _arg| {
+    ///         cx.resolve::<&Pool>();
+    ///         Journal
+    ///     }),
+    /// );
+    ///
+    /// cx.resolve::<&Journal>();
+    ///
+    /// let err = cx.shutdown().unwrap_err();
+    /// assert!(err.to_string().ends_with("disk full"));
+    /// assert_eq!(*LOG.lock().unwrap(), ["journal synced", "pool flushed"]);
+    /// ```
+    ///
+    /// # Panics
+    /// If the service binding fails. See [`try_bind_disposable`](Self::try_bind_disposable) for a
+    /// fallible version of this function.
+    #[track_caller]
+    pub fn bind_disposable<'cx, S>(
+        &'cx mut self,
+        lifetime: Lifetime,
+        provider: impl Provider<'cx, S>,
+    ) where
+        S: Service + ?Sized,
+        S::Output<'cx>: Dispose + Send + Sync,
+    {
+        if let Err(err) = self.try_bind_disposable::<S>(lifetime, provider) {
+            panic!("{}", err)
+        }
+    }
+
+    /// Register a new provider for the service `S`, replacing any provider bound to it by a parent
+    /// context
+    ///
+    /// The parent's provider is left untouched: It is only hidden from this context and its
+    /// sub-contexts.
+    ///
+    /// ```
+    /// # use dfdi::{provider_fn, Context, Service};
+    /// #[derive(Service)]
+    /// struct Clock(u64);
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_fn::<Clock>(|_cx, _arg| Clock(1_000));
+    ///
+    /// let mut test = cx.scoped();
+    /// test.override_with(provider_fn::<Clock>(|_cx, _arg| Clock(0)));
+    ///
+    /// assert_eq!(test.resolve::<Clock>().0, 0);
+    /// assert_eq!(cx.resolve::<Clock>().0, 1_000);
+    /// ```
+    ///
+    /// # Panics
+    /// If the service overriding fails. See [`try_override_with`](Self::try_override_with) for a
+    /// fallible version of this function.
+    #[track_caller]
+    pub fn override_with<'cx, S: Service>(&'cx mut self, provider: impl Provider<'cx, S>) {
+        if let Err(err) = self.try_override_with::<S>(provider) {
+            panic!("{}", err)
+        }
+    }
+
+    /// Wrap the provider bound to the service `S` with a decorator
+    ///
+    /// The decorator is called instead of the provider, with a function that runs the provider it
+    /// wraps. Decorators added later wrap the ones added before them. A decorator added to a
+    /// sub-context only applies to that context and its own sub-contexts.
+    ///
+    /// ```
+    /// # use dfdi::{Context, Service};
+    /// #[derive(Service)]
+    /// struct Greeting(String);
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_fn::<Greeting>(|_cx, _arg| Greeting("hello".to_string()));
+    /// cx.decorate::<Greeting>(|_cx, arg, next| {
+    ///     let Greeting(greeting) = next(arg)?;
+    ///     Ok(Greeting(format!("{greeting}, world")))
+    /// });
+    ///
+    /// let mut scope = cx.scoped();
+    /// scope.decorate::<Greeting>(|_cx, arg, next| Ok(Greeting(next(arg)?.0.to_uppercase())));
+    ///
+    /// assert_eq!(scope.resolve::<Greeting>().0, "HELLO, WORLD");
+    /// assert_eq!(cx.resolve::<Greeting>().0, "hello, world");
+    /// ```
+    ///
+    /// # Panics
+    /// If the service decoration fails. See [`try_decorate`](Self::try_decorate) for a fallible
+    /// version of this function.
+    #[track_caller]
+    pub fn decorate<'cx, S: Service>(
+        &'cx mut self,
+        decorator: impl Fn(
+                &'cx Context,
+                S::Argument<'_>,
+                &dyn Fn(S::Argument<'_>) -> Result<S::Output<'cx>, Box<dyn Error + Send + Sync>>,
+            ) -> Result<S::Output<'cx>, Box<dyn Error + Send + Sync>>
+            + Send
+            + Sync
+            + 'cx,
+    ) {
+        if let Err(err) = self.try_decorate::<S>(decorator) {
+            panic!("{}", err)
+        }
+    }
+
     /// Delete the provider bound to the service `S`
     ///
     /// # Panics
@@ -115,8 +535,8 @@ impl Context<'_> {
     /// Resolve the service `S` using the default service argument.
     ///
     /// # Panics
-    /// If no provider is registered for this service. See [`try_resolve`](Self::try_resolve) for a
-    /// fallible version of this function.
+    /// If the service resolution fails. See [`try_resolve`](Self::try_resolve) for a fallible
+    /// version of this function.
     #[inline(always)]
     #[track_caller]
     pub fn resolve<S>(&self) -> S::Output<'_>
@@ -130,40 +550,79 @@ impl Context<'_> {
     /// Resolve the service `S` given the service argument.
     ///
     /// # Panics
-    /// If no provider is registered for this service. See [`try_resolve`](Self::try_resolve) for a
-    /// fallible version of this function.
+    /// If the service resolution fails. The panic message includes the chain of services that was
+    /// being resolved. See [`try_resolve_with`](Self::try_resolve_with) for a fallible version of
+    /// this function.
     #[track_caller]
     pub fn resolve_with<S>(&self, arg: S::Argument<'_>) -> S::Output<'_>
     where
         S: Service,
     {
         match self.try_resolve_with::<S>(arg) {
-            Some(s) => s,
-            None => panic!("no provider for service `{}`", type_name::<S>()),
+            Ok(s) => s,
+            Err(err) => panic!("{}", stack::with_chain(err)),
         }
     }
 
+    /// Resolve the service `S` using the default service argument, or compute a fallback if no
+    /// provider is bound to it.
+    ///
+    /// ```
+    /// # use dfdi::{Context, Service};
+    /// #[derive(Service)]
+    /// struct Tracer(&'static str);
+    ///
+    /// let mut cx = Context::new();
+    /// assert_eq!(cx.resolve_or_else::<Tracer>(|| Tracer("noop")).0, "noop");
+    ///
+    /// cx.bind_fn::<Tracer>(|_cx, _arg| Tracer("stdout"));
+    /// assert_eq!(cx.resolve_or_else::<Tracer>(|| Tracer("noop")).0, "stdout");
+    /// ```
+    ///
+    /// # Panics
+    /// If the service is bound, but its resolution fails.
+    #[inline(always)]
+    #[track_caller]
+    pub fn resolve_or_else<'cx, S>(
+        &'cx self,
+        fallback: impl FnOnce() -> S::Output<'cx>,
+    ) -> S::Output<'cx>
+    where
+        S: Service,
+        S::Argument<'static>: Default,
+    {
+        self.resolve::<Option<S>>().unwrap_or_else(fallback)
+    }
+
+    /// Resolve the service `S` using the default service argument, or fall back to the default
+    /// output if no provider is bound to it.
+    ///
+    /// # Panics
+    /// If the service is bound, but its resolution fails.
+    #[inline(always)]
+    #[track_caller]
+    pub fn resolve_or_default<'cx, S>(&'cx self) -> S::Output<'cx>
+    where
+        S: Service,
+        S::Argument<'static>: Default,
+        S::Output<'cx>: Default,
+    {
+        self.resolve::<Option<S>>().unwrap_or_default()
+    }
+
     /// Try to register a new provider for the service `S`
     ///
     /// # Fails
     /// This function will fail if a provider is already bound to the service.
     ///
     /// See [`bind_with`](Self::bind_with) for the panicking version of this function.
+    #[inline(always)]
+    #[track_caller]
     pub fn try_bind_with<'cx, S: Service>(
         &'cx mut self,
         provider: impl Provider<'cx, S>,
     ) -> Result<(), BindError> {
-        use std::collections::hash_map::Entry::*;
-        match self.providers.entry(TypeId::of::<S>()) {
-            Vacant(e) => {
-                // SAFETY:
-                // - Due to the api provided by `Context`, all clones of `DynProvider` _will_ be
-                //   dropped before the original instance is dropped
-                e.insert(unsafe { DynProvider::new(provider) });
-                Ok(())
-            }
-            Occupied(_) => Err(BindError::ServiceBound(std::any::type_name::<S>())),
-        }
+        self.bind_key::<S>(Key::of::<S>(None), DynProvider::new(provider))
     }
 
     /// Try to register a function as a provider for the service `S`
@@ -173,6 +632,7 @@ impl Context<'_> {
     ///
     /// See [`bind_fn`](Self::bind_fn) for the panicking version of this function.
     #[inline(always)]
+    #[track_caller]
     pub fn try_bind_fn<'cx, S: Service>(
         &'cx mut self,
         provider_fn: impl Fn(&'cx Context, S::Argument<'_>) -> S::Output<'cx> + Send + Sync + 'cx,
@@ -187,6 +647,7 @@ impl Context<'_> {
     ///
     /// See [`bind`](Self::bind) for the panicking version of this function.
     #[inline(always)]
+    #[track_caller]
     pub fn try_bind<'cx, S, P>(&'cx mut self) -> Result<(), BindError>
     where
         S: Service,
@@ -195,30 +656,180 @@ impl Context<'_> {
         self.try_bind_with(P::default())
     }
 
+    /// Try to bind the trait object service `T` to a default instance of the concrete type `C`
+    ///
+    /// # Fails
+    /// This function will fail if a provider is already bound to `&T`.
+    ///
+    /// See [`bind_as`](Self::bind_as) for the panicking version of this function.
+    #[inline(always)]
+    #[track_caller]
+    pub fn try_bind_as<'cx, T, C>(&'cx mut self) -> Result<(), BindError>
+    where
+        T: Upcast<C> + ?Sized,
+        T::Output<'cx>: Send + Sync,
+        C: Default + 'cx,
+    {
+        self.try_bind_with::<&'static T>(Upcasted::<T>(T::upcast(C::default())))
+    }
+
+    /// Try to bind the trait object service `T` to the output of a provider of the service `C`
+    ///
+    /// # Fails
+    /// This function will fail if a provider is already bound to `&T`.
+    ///
+    /// See [`bind_as_with`](Self::bind_as_with) for the panicking version of this function.
+    #[inline(always)]
+    #[track_caller]
+    pub fn try_bind_as_with<'cx, T, C>(
+        &'cx mut self,
+        provider: impl Provider<'cx, C>,
+    ) -> Result<(), BindError>
+    where
+        T: Upcast<C::Output<'cx>> + ?Sized,
+        T::Output<'cx>: Send + Sync,
+        C: Service + ?Sized,
+        C::Output<'cx>: 'cx,
+        C::Argument<'static>: Default,
+    {
+        self.try_bind_with::<&'static T>(UpcastedWith::<T, C, _>::new(provider))
+    }
+
+    /// Try to register a new provider for the service `S`, whose instances are shared according
+    /// to the given [`Lifetime`]
+    ///
+    /// # Fails
+    /// This function will fail if a provider is already bound to `&S`, or to `S` for transient
+    /// bindings.
+    ///
+    /// See [`bind_with_lifetime`](Self::bind_with_lifetime) for the panicking version of this
+    /// function.
+    #[track_caller]
+    pub fn try_bind_with_lifetime<'cx, S>(
+        &'cx mut self,
+        lifetime: Lifetime,
+        provider: impl Provider<'cx, S>,
+    ) -> Result<(), BindError>
+    where
+        S: Service + ?Sized,
+        S::Output<'cx>: Send + Sync,
+    {
+        match lifetime {
+            // Transient instances are handed out by value, so they do not need to be kept
+            Lifetime::Transient => {
+                self.bind_key::<S>(Key::of::<S>(None), DynProvider::new(provider))
+            }
+            _ => self.bind_lifetime::<S>(WithLifetime::new(lifetime, provider)),
+        }
+    }
+
+    /// Try to register a new provider for the service `S`, whose instances are shared according
+    /// to the given [`Lifetime`] and disposed of by the context that owns them
+    ///
+    /// # Fails
+    /// This function will fail if a provider is already bound to `&S`, or if the lifetime is
+    /// [`Lifetime::Transient`]: Transient instances are handed out by value, so they must be
+    /// disposed of by their owner.
+    ///
+    /// See [`bind_disposable`](Self::bind_disposable) for the panicking version of this function.
+    #[track_caller]
+    pub fn try_bind_disposable<'cx, S>(
+        &'cx mut self,
+        lifetime: Lifetime,
+        provider: impl Provider<'cx, S>,
+    ) -> Result<(), BindError>
+    where
+        S: Service + ?Sized,
+        S::Output<'cx>: Dispose + Send + Sync,
+    {
+        if lifetime == Lifetime::Transient {
+            return Err(BindError::TransientDisposable(type_name::<S>()));
+        }
+
+        let disposer = Disposer::new::<S::Output<'cx>>(type_name::<S>());
+        let provider = WithLifetime::new(lifetime, provider).with_disposer(disposer);
+        self.bind_lifetime::<S>(provider)
+    }
+
+    /// Try to register a new provider for the service `S`, replacing any provider bound to it by a
+    /// parent context
+    ///
+    /// # Fails
+    /// This function will fail if a provider is already bound to the service in this context.
+    ///
+    /// See [`override_with`](Self::override_with) for the panicking version of this function.
+    #[track_caller]
+    pub fn try_override_with<'cx, S: Service>(
+        &'cx mut self,
+        provider: impl Provider<'cx, S>,
+    ) -> Result<(), BindError> {
+        let key = Key::of::<S>(None);
+        if let Some(Some(_)) = self.providers.get(&key) {
+            return Err(BindError::ServiceBound(type_name::<S>(), None));
+        }
+
+        self.providers.insert(key, Some(DynProvider::new(provider)));
+        Ok(())
+    }
+
+    /// Try to wrap the provider bound to the service `S` with a decorator
+    ///
+    /// # Fails
+    /// This function will fail if no provider is bound to the service.
+    ///
+    /// See [`decorate`](Self::decorate) for the panicking version of this function.
+    #[track_caller]
+    pub fn try_decorate<'cx, S: Service>(
+        &'cx mut self,
+        decorator: impl Fn(
+                &'cx Context,
+                S::Argument<'_>,
+                &dyn Fn(S::Argument<'_>) -> Result<S::Output<'cx>, Box<dyn Error + Send + Sync>>,
+            ) -> Result<S::Output<'cx>, Box<dyn Error + Send + Sync>>
+            + Send
+            + Sync
+            + 'cx,
+    ) -> Result<(), BindError> {
+        let key = Key::of::<S>(None);
+
+        // Providers bound in this context are moved into the decorator, while the ones bound by a
+        // parent context are borrowed from it
+        let inner = match self.providers.get(&key) {
+            Some(Some(_)) => Inner::Owned(self.providers.remove(&key).flatten().unwrap()),
+            Some(None) => return Err(BindError::ServiceUnbound(type_name::<S>(), None)),
+            None => match self.parent.and_then(|parent| parent.binding(key)) {
+                Some((owner, provider)) => Inner::Inherited(owner, provider),
+                None => return Err(BindError::ServiceUnbound(type_name::<S>(), None)),
+            },
+        };
+
+        let provider = DynProvider::new::<S, _>(Decorated { inner, decorator });
+        self.providers.insert(key, Some(provider));
+        Ok(())
+    }
+
     /// Try to delete the provider bound to the service `S`.
     ///
     /// # Fails
     /// This function will fail if no provider is bound to the service.
     ///
     /// See [`unbind`](Self::unbind) for the panicking version of this function.
+    #[inline(always)]
     pub fn try_unbind<S>(&mut self) -> Result<(), UnbindError>
     where
         S: Service,
     {
-        match self.providers.remove(&TypeId::of::<S>()) {
-            Some(_) => Ok(()),
-            None => Err(UnbindError::ServiceUnbound(type_name::<S>())),
-        }
+        self.unbind_key::<S>(Key::of::<S>(None))
     }
 
     /// Try to resolve the service `S` using the default service argument.
     ///
     /// # Fails
-    /// This function will fail if no provider is bound to the service.
+    /// This function will fail if no provider is bound to the service, or if the provider fails.
     ///
     /// See [`resolve`](Self::resolve) for the panicking version of this function.
     #[inline(always)]
-    pub fn try_resolve<S>(&self) -> Option<S::Output<'_>>
+    pub fn try_resolve<S>(&self) -> Result<S::Output<'_>, ResolveError>
     where
         S: Service,
         S::Argument<'static>: Default,
@@ -229,26 +840,1190 @@ impl Context<'_> {
     /// Try to resolve the service `S` given the service argument.
     ///
     /// # Fails
-    /// This function will fail if no provider is bound to the service.
+    /// This function will fail if no provider is bound to the service, or if the provider fails.
+    ///
+    /// It will also fail if the service is already being resolved on the current thread, since
+    /// that would be a dependency cycle. Note that this applies even if the service argument is
+    /// different.
+    ///
+    /// ```
+    /// # use dfdi::{Context, Fallible, ResolveError, Service};
+    /// #[derive(Service)]
+    /// struct Chicken;
+    ///
+    /// #[derive(Service)]
+    /// struct Egg;
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_with::<Chicken>(Fallible::new(|cx, _arg| cx.try_resolve::<Egg>().map(|_| Chicken)));
+    /// cx.bind_with::<Egg>(Fallible::new(|cx, _arg| cx.try_resolve::<Chicken>().map(|_| Egg)));
+    ///
+    /// let err = cx.try_resolve::<Chicken>().err().unwrap();
+    /// assert!(matches!(err.root_cause(), ResolveError::Cycle(cycle) if cycle.len() == 3));
+    /// ```
     ///
     /// See [`resolve_with`](Self::resolve_with) for the panicking version of this function.
-    pub fn try_resolve_with<S>(&self, arg: S::Argument<'_>) -> Option<S::Output<'_>>
+    #[inline(always)]
+    pub fn try_resolve_with<S>(&self, arg: S::Argument<'_>) -> Result<S::Output<'_>, ResolveError>
     where
         S: Service,
     {
-        let provider = self.providers.get(&TypeId::of::<S>())?;
-
-        // SAFETY:
-        // - We know that the provider was created for the service `S`, since it came from the
-        //   `self.providers` map
-        Some(unsafe { provider.provide::<S>(self, arg) })
+        S::resolve_in(self, None, arg)
     }
-}
 
-impl Default for Context<'_> {
-    #[inline(always)]
-    fn default() -> Self {
-        Self::new()
+    /// Register a new provider for the service `S` under the given name
+    ///
+    /// Named bindings are independent of each other and of the unnamed binding of the same service,
+    /// which allows binding multiple providers to a single service type.
+    ///
+    /// ```
+    /// # use dfdi::{provider_fn, Context, Service};
+    /// #[derive(Service)]
+    /// struct Pool(&'static str);
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_named("primary", provider_fn::<Pool>(|_cx, _arg| Pool("db-1")));
+    /// cx.bind_named("replica", provider_fn::<Pool>(|_cx, _arg| Pool("db-2")));
+    ///
+    /// assert_eq!(cx.resolve_named::<Pool>("replica").0, "db-2");
+    /// assert!(cx.try_resolve::<Pool>().is_err());
+    /// ```
+    ///
+    /// # Panics
+    /// If the service binding fails. See [`try_bind_named`](Self::try_bind_named) for a fallible
+    /// version of this function.
+    #[track_caller]
+    pub fn bind_named<'cx, S: Service>(
+        &'cx mut self,
+        name: &'static str,
+        provider: impl Provider<'cx, S>,
+    ) {
+        if let Err(err) = self.try_bind_named::<S>(name, provider) {
+            panic!("{}", err)
+        }
+    }
+
+    /// Delete the provider bound to the service `S` under the given name
+    ///
+    /// # Panics
+    /// If the service unbinding fails. See [`try_unbind_named`](Self::try_unbind_named) for a
+    /// fallible version of this function.
+    #[track_caller]
+    pub fn unbind_named<S>(&mut self, name: &'static str)
+    where
+        S: Service,
+    {
+        if let Err(err) = self.try_unbind_named::<S>(name) {
+            panic!("{}", err)
+        }
+    }
+
+    /// Resolve the service `S` bound under the given name using the default service argument.
+    ///
+    /// # Panics
+    /// If the service resolution fails. See [`try_resolve_named`](Self::try_resolve_named) for a
+    /// fallible version of this function.
+    #[inline(always)]
+    #[track_caller]
+    pub fn resolve_named<S>(&self, name: &'static str) -> S::Output<'_>
+    where
+        S: Service,
+        S::Argument<'static>: Default,
+    {
+        self.resolve_named_with::<S>(name, Default::default())
+    }
+
+    /// Resolve the service `S` bound under the given name given the service argument.
+    ///
+    /// # Panics
+    /// If the service resolution fails. The panic message includes the chain of services that was
+    /// being resolved. See [`try_resolve_named_with`](Self::try_resolve_named_with) for a fallible
+    /// version of this function.
+    #[track_caller]
+    pub fn resolve_named_with<S>(&self, name: &'static str, arg: S::Argument<'_>) -> S::Output<'_>
+    where
+        S: Service,
+    {
+        match self.try_resolve_named_with::<S>(name, arg) {
+            Ok(s) => s,
+            Err(err) => panic!("{}", stack::with_chain(err)),
+        }
+    }
+
+    /// Try to register a new provider for the service `S` under the given name
+    ///
+    /// # Fails
+    /// This function will fail if a provider is already bound to the service under this name.
+    ///
+    /// See [`bind_named`](Self::bind_named) for the panicking version of this function.
+    #[inline(always)]
+    #[track_caller]
+    pub fn try_bind_named<'cx, S: Service>(
+        &'cx mut self,
+        name: &'static str,
+        provider: impl Provider<'cx, S>,
+    ) -> Result<(), BindError> {
+        self.bind_key::<S>(Key::of::<S>(Some(name)), DynProvider::new(provider))
+    }
+
+    /// Try to delete the provider bound to the service `S` under the given name.
+    ///
+    /// # Fails
+    /// This function will fail if no provider is bound to the service under this name.
+    ///
+    /// See [`unbind_named`](Self::unbind_named) for the panicking version of this function.
+    #[inline(always)]
+    pub fn try_unbind_named<S>(&mut self, name: &'static str) -> Result<(), UnbindError>
+    where
+        S: Service,
+    {
+        self.unbind_key::<S>(Key::of::<S>(Some(name)))
+    }
+
+    /// Try to resolve the service `S` bound under the given name using the default service
+    /// argument.
+    ///
+    /// # Fails
+    /// This function will fail if no provider is bound to the service under this name, or if the
+    /// provider fails.
+    ///
+    /// See [`resolve_named`](Self::resolve_named) for the panicking version of this function.
+    #[inline(always)]
+    pub fn try_resolve_named<S>(&self, name: &'static str) -> Result<S::Output<'_>, ResolveError>
+    where
+        S: Service,
+        S::Argument<'static>: Default,
+    {
+        self.try_resolve_named_with::<S>(name, Default::default())
+    }
+
+    /// Try to resolve the service `S` bound under the given name given the service argument.
+    ///
+    /// # Fails
+    /// This function will fail if no provider is bound to the service under this name, or if the
+    /// provider fails.
+    ///
+    /// See [`resolve_named_with`](Self::resolve_named_with) for the panicking version of this
+    /// function.
+    #[inline(always)]
+    pub fn try_resolve_named_with<S>(
+        &self,
+        name: &'static str,
+        arg: S::Argument<'_>,
+    ) -> Result<S::Output<'_>, ResolveError>
+    where
+        S: Service,
+    {
+        S::resolve_in(self, Some(name), arg)
+    }
+
+    /// Add a provider to the collection of providers for the service `S`
+    ///
+    /// Unlike regular bindings, any number of providers can be added to a service this way. They are
+    /// kept separately from regular bindings, and can only be resolved all together through
+    /// [`resolve_all`](Self::resolve_all).
+    ///
+    /// ```
+    /// # use dfdi::{provider_fn, Context, Service};
+    /// #[derive(Service)]
+    /// struct HealthCheck(&'static str);
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_many(provider_fn::<HealthCheck>(|_cx, _arg| HealthCheck("database")));
+    ///
+    /// let mut scope = cx.scoped();
+    /// scope.bind_many(provider_fn::<HealthCheck>(|_cx, _arg| HealthCheck("cache")));
+    ///
+    /// let checks = |cx: &Context| cx.resolve_all::<HealthCheck>().map(|c| c.0).collect::<Vec<_>>();
+    /// assert_eq!(checks(&scope), ["database", "cache"]);
+    /// assert_eq!(checks(&cx), ["database"]);
+    /// ```
+    #[track_caller]
+    pub fn bind_many<'cx, S: Service>(&'cx mut self, provider: impl Provider<'cx, S>) {
+        self.multi_providers
+            .entry(TypeId::of::<S>())
+            .or_default()
+            .push(DynProvider::new(provider));
+    }
+
+    /// Resolve the service `S` with every provider added through [`bind_many`](Self::bind_many),
+    /// using the default service argument.
+    ///
+    /// Providers are called lazily, in the order they were added. Providers added to a parent
+    /// context come before the ones added to its sub-contexts.
+    ///
+    /// # Panics
+    /// If any of the providers fail. The panic message includes the chain of services that was
+    /// being resolved.
+    pub fn resolve_all<S>(&self) -> impl Iterator<Item = S::Output<'_>>
+    where
+        S: Service,
+        S::Argument<'static>: Default,
+    {
+        let mut providers = Vec::new();
+        self.collect_multi_providers(TypeId::of::<S>(), &mut providers);
+
+        providers.into_iter().map(move |(owner, provider)| {
+            let cx = self.provider_context(owner, provider);
+
+            // SAFETY:
+            // - We know that the provider was created for the service `S`, since it was added to
+            //   the list for its `TypeId`
+            match unsafe { cx.provide::<S>(Key::many::<S>(), provider, Default::default()) } {
+                Ok(s) => s,
+                Err(err) => panic!("{}", stack::with_chain(err)),
+            }
+        })
+    }
+
+    /// Register a new async provider for the service `S`
+    ///
+    /// Services bound to async providers can only be resolved through
+    /// [`resolve_async`](Self::resolve_async) and its variants.
+    ///
+    /// # Panics
+    /// If the service binding fails. See [`try_bind_async_with`](Self::try_bind_async_with) for a
+    /// fallible version of this function.
+    #[track_caller]
+    pub fn bind_async_with<'cx, S: Service>(&'cx mut self, provider: impl AsyncProvider<'cx, S>) {
+        if let Err(err) = self.try_bind_async_with::<S>(provider) {
+            panic!("{}", err)
+        }
+    }
+
+    /// Register an async function as a provider for the service `S`
+    ///
+    /// # Panics
+    /// If the service binding fails. See [`try_bind_async_fn`](Self::try_bind_async_fn) for a
+    /// fallible version of this function.
+    #[track_caller]
+    pub fn bind_async_fn<'cx, S, Fut>(
+        &'cx mut self,
+        provider_fn: impl Fn(&'cx Context, S::Argument<'cx>) -> Fut + Send + Sync + 'cx,
+    ) where
+        S: Service,
+        Fut: Future<Output = S::Output<'cx>> + Send + 'cx,
+    {
+        if let Err(err) = self.try_bind_async_fn::<S, Fut>(provider_fn) {
+            panic!("{}", err)
+        }
+    }
+
+    /// Resolve the service `S` asynchronously using the default service argument.
+    ///
+    /// # Panics
+    /// If the service resolution fails. See [`try_resolve_async`](Self::try_resolve_async) for a
+    /// fallible version of this function.
+    #[inline(always)]
+    pub async fn resolve_async<'cx, S>(&'cx self) -> S::Output<'cx>
+    where
+        S: Service,
+        S::Argument<'cx>: Default,
+    {
+        self.resolve_async_with::<S>(Default::default()).await
+    }
+
+    /// Resolve the service `S` asynchronously given the service argument.
+    ///
+    /// # Panics
+    /// If the service resolution fails. The panic message includes the chain of services that was
+    /// being resolved. See [`try_resolve_async_with`](Self::try_resolve_async_with) for a fallible
+    /// version of this function.
+    pub async fn resolve_async_with<'cx, S>(&'cx self, arg: S::Argument<'cx>) -> S::Output<'cx>
+    where
+        S: Service,
+    {
+        match self.try_resolve_async_with::<S>(arg).await {
+            Ok(s) => s,
+            Err(err) => panic!("{}", stack::with_chain(err)),
+        }
+    }
+
+    /// Try to register a new async provider for the service `S`
+    ///
+    /// # Fails
+    /// This function will fail if a provider is already bound to the service.
+    ///
+    /// See [`bind_async_with`](Self::bind_async_with) for the panicking version of this function.
+    #[track_caller]
+    pub fn try_bind_async_with<'cx, S: Service>(
+        &'cx mut self,
+        provider: impl AsyncProvider<'cx, S>,
+    ) -> Result<(), BindError> {
+        self.bind_key::<S>(Key::of::<S>(None), DynProvider::new_async(provider))
+    }
+
+    /// Try to register an async function as a provider for the service `S`
+    ///
+    /// # Fails
+    /// This function will fail if a provider is already bound to the service.
+    ///
+    /// See [`bind_async_fn`](Self::bind_async_fn) for the panicking version of this function.
+    #[inline(always)]
+    #[track_caller]
+    pub fn try_bind_async_fn<'cx, S, Fut>(
+        &'cx mut self,
+        provider_fn: impl Fn(&'cx Context, S::Argument<'cx>) -> Fut + Send + Sync + 'cx,
+    ) -> Result<(), BindError>
+    where
+        S: Service,
+        Fut: Future<Output = S::Output<'cx>> + Send + 'cx,
+    {
+        self.try_bind_async_with::<S>(provider_fn)
+    }
+
+    /// Try to resolve the service `S` asynchronously using the default service argument.
+    ///
+    /// # Fails
+    /// This function will fail if no provider is bound to the service, or if the provider fails.
+    ///
+    /// See [`resolve_async`](Self::resolve_async) for the panicking version of this function.
+    #[inline(always)]
+    pub async fn try_resolve_async<'cx, S>(&'cx self) -> Result<S::Output<'cx>, ResolveError>
+    where
+        S: Service,
+        S::Argument<'cx>: Default,
+    {
+        self.try_resolve_async_with::<S>(Default::default()).await
+    }
+
+    /// Try to resolve the service `S` asynchronously given the service argument.
+    ///
+    /// Services bound to regular providers are resolved synchronously, exactly like
+    /// [`try_resolve_with`](Self::try_resolve_with). Services bound to async providers have some
+    /// limitations:
+    /// - The provider is not tracked while it is running, so dependency cycles going through it
+    ///   are not detected, and it is missing from the chain of services reported when one of its
+    ///   dependencies fails.
+    /// - Its resolutions are not recorded in the [`dependency_graph`](Self::dependency_graph).
+    /// - Only the unnamed binding of the service can be resolved, since there is no named variant
+    ///   of this function.
+    ///
+    /// # Fails
+    /// This function will fail if no provider is bound to the service, or if the provider fails.
+    ///
+    /// See [`resolve_async_with`](Self::resolve_async_with) for the panicking version of this
+    /// function.
+    pub async fn try_resolve_async_with<'cx, S>(
+        &'cx self,
+        arg: S::Argument<'cx>,
+    ) -> Result<S::Output<'cx>, ResolveError>
+    where
+        S: Service,
+    {
+        let key = Key::of::<S>(None);
+
+        // Note: The provider reference must not be held across the await point, otherwise the
+        // returned future would not be `Send`.
+        let future = {
+            let (owner, provider) = self
+                .binding(key)
+                .ok_or_else(|| ResolveError::ServiceUnbound(type_name::<S>(), None))?;
+
+            if !provider.is_async {
+                // SAFETY:
+                // - We know that the provider was created for the service `S`, since it was bound
+                //   to a key with its `TypeId`
+                return unsafe {
+                    self.provider_context(owner, provider)
+                        .provide::<S>(key, provider, arg)
+                };
+            }
+
+            // SAFETY:
+            // - We know that the provider was created for the service `S`, since it was bound to a
+            //   key with its `TypeId`
+            // - We just checked that it is an async provider
+            unsafe { provider.provide_async::<S>(self.provider_context(owner, provider), arg) }
+        };
+
+        future.await.map_err(provider_error::<S>)
+    }
+
+    /// Register the bindings of a module
+    ///
+    /// Installing a module that was already installed in this context or one of its parents does
+    /// nothing.
+    ///
+    /// ```
+    /// # use dfdi::{BindError, Context, Module, Service};
+    /// #[derive(Service)]
+    /// struct Logger(&'static str);
+    ///
+    /// #[derive(Service)]
+    /// struct Metrics(&'static str);
+    ///
+    /// struct LoggingModule;
+    ///
+    /// impl Module for LoggingModule {
+    ///     fn register(self, cx: &mut Context) -> Result<(), BindError> {
+    ///         cx.try_bind_fn::<Logger>(|_cx, _arg| Logger("stderr"))
+    ///     }
+    /// }
+    ///
+    /// struct MetricsModule;
+    ///
+    /// impl Module for MetricsModule {
+    ///     fn register(self, cx: &mut Context) -> Result<(), BindError> {
+    ///         cx.try_install(LoggingModule)?;
+    ///         cx.try_bind_fn::<Metrics>(|_cx, _arg| Metrics("statsd"))
+    ///     }
+    /// }
+    ///
+    /// let mut cx = Context::new();
+    /// cx.install(LoggingModule);
+    /// cx.install(MetricsModule);
+    ///
+    /// assert_eq!(cx.resolve::<Logger>().0, "stderr");
+    /// assert_eq!(cx.resolve::<Metrics>().0, "statsd");
+    /// ```
+    ///
+    /// # Panics
+    /// If the module fails to register its bindings. See [`try_install`](Self::try_install) for a
+    /// fallible version of this function.
+    #[track_caller]
+    pub fn install<M: Module>(&mut self, module: M) {
+        if let Err(err) = self.try_install(module) {
+            panic!("{}", err)
+        }
+    }
+
+    /// Try to register the bindings of a module
+    ///
+    /// Installing a module that was already installed in this context or one of its parents does
+    /// nothing.
+    ///
+    /// If the module fails, the bindings it added are removed, along with the modules it installed,
+    /// so that installing it again succeeds once the conflict is resolved. Bindings that it
+    /// decorated or unbound are not restored.
+    ///
+    /// ```
+    /// # use dfdi::{BindError, Context, Module, Service};
+    /// #[derive(Service)]
+    /// struct Logger;
+    ///
+    /// #[derive(Service)]
+    /// struct Metrics;
+    ///
+    /// struct TelemetryModule;
+    ///
+    /// impl Module for TelemetryModule {
+    ///     fn register(self, cx: &mut Context) -> Result<(), BindError> {
+    ///         cx.try_bind_fn::<Logger>(|_cx, _arg| Logger)?;
+    ///         cx.try_bind_fn::<Metrics>(|_cx, _arg| Metrics)
+    ///     }
+    /// }
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_fn::<Metrics>(|_cx, _arg| Metrics);
+    ///
+    /// assert!(cx.try_install(TelemetryModule).is_err());
+    /// assert!(cx.try_resolve::<Logger>().is_err());
+    ///
+    /// cx.unbind::<Metrics>();
+    /// assert!(cx.try_install(TelemetryModule).is_ok());
+    /// ```
+    ///
+    /// # Fails
+    /// This function will fail if the module fails to register its bindings. The error names the
+    /// module, along with any modules it was installed by.
+    ///
+    /// See [`install`](Self::install) for the panicking version of this function.
+    #[track_caller]
+    pub fn try_install<M: Module>(&mut self, module: M) -> Result<(), BindError> {
+        let id = TypeId::of::<M>();
+        if self.is_installed(id) {
+            return Ok(());
+        }
+
+        // What is bound before registering the module, so that its bindings can be removed if it
+        // fails
+        let providers: HashSet<Key> = self.providers.keys().copied().collect();
+        let multi_providers: HashMap<TypeId, usize> = self
+            .multi_providers
+            .iter()
+            .map(|(id, providers)| (*id, providers.len()))
+            .collect();
+        let modules = self.modules.clone();
+
+        self.modules.insert(id);
+        module.register(self).map_err(|err| {
+            self.providers.retain(|key, _| providers.contains(key));
+            self.multi_providers
+                .retain(|id, providers| match multi_providers.get(id) {
+                    Some(len) => {
+                        providers.truncate(*len);
+                        true
+                    }
+                    None => false,
+                });
+            self.modules = modules;
+
+            BindError::Module(type_name::<M>(), Box::new(err))
+        })
+    }
+
+    /// Describe the services bound in this context and its parents
+    ///
+    /// Bindings are sorted by service type name and name. Providers added through
+    /// [`bind_many`](Self::bind_many) are not included.
+    ///
+    /// ```
+    /// # use dfdi::{Context, Service};
+    /// #[derive(Service)]
+    /// struct Config;
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_fn::<Config>(|_cx, _arg| Config);
+    ///
+    /// let scope = cx.scoped();
+    /// let services: Vec<_> = scope.services().collect();
+    /// assert_eq!(services.len(), 1);
+    /// assert!(services[0].service.ends_with("Config"));
+    /// assert!(services[0].inherited);
+    /// assert_eq!(services[0].location.line(), line!() - 7);
+    /// ```
+    pub fn services(&self) -> impl Iterator<Item = ServiceDescriptor> {
+        self.bindings()
+            .into_iter()
+            .map(|binding| ServiceDescriptor {
+                service: binding.provider.service,
+                name: binding.key.name,
+                provider: binding.provider.provider,
+                location: binding.provider.location,
+                inherited: binding.inherited,
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Check that the dependencies declared by the providers of this context and its parents are
+    /// bound, and that they do not form any cycles, without constructing any service
+    ///
+    /// Providers declare their dependencies through [`Provider::dependencies`]. Services deriving
+    /// `Inject` declare them automatically.
+    ///
+    /// ```
+    /// # use dfdi::{Context, Inject, Injector, Service, ValidationError};
+    /// #[derive(Service)]
+    /// struct Config;
+    ///
+    /// #[derive(Service, Inject)]
+    /// struct Database<'cx> {
+    ///     config: &'cx Config,
+    /// }
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind::<Database, Injector<Database>>();
+    ///
+    /// let errors = cx.validate().unwrap_err();
+    /// assert!(matches!(errors[..], [ValidationError::MissingDependency(..)]));
+    /// ```
+    ///
+    /// Services that depend on each other form a cycle, which is reported once:
+    /// ```
+    /// # use dfdi::{Cached, Context, Inject, Injector, Service, ValidationError};
+    /// #[derive(Service, Inject)]
+    /// struct Orders<'cx> {
+    ///     customers: &'cx Customers<'cx>,
+    /// }
+    ///
+    /// #[derive(Service, Inject)]
+    /// struct Customers<'cx> {
+    ///     orders: &'cx Orders<'cx>,
+    /// }
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_with::<&Orders>(Cached::new(Injector::<Orders>::default()));
+    /// cx.bind_with::<&Customers>(Cached::new(Injector::<Customers>::default()));
+    ///
+    /// let errors = cx.validate().unwrap_err();
+    /// let [ValidationError::Cycle(cycle)] = &errors[..] else {
+    ///     panic!("expected a single cycle, found {errors:?}");
+    /// };
+    /// assert_eq!(cycle.len(), 3);
+    /// assert_eq!(cycle.first(), cycle.last());
+    /// ```
+    ///
+    /// Dependencies resolved through `Lazy` or [`Factory`](crate::Factory) are only resolved after
+    /// the provider has run, so they do not form cycles:
+    /// ```
+    /// # use dfdi::{Cached, Context, Factory, Inject, Injector, Lazy, Service};
+    /// #[derive(Service, Inject)]
+    /// struct Orders<'cx> {
+    ///     customers: Lazy<'cx, &'static Customers<'static>>,
+    /// }
+    ///
+    /// #[derive(Service, Inject)]
+    /// struct Customers<'cx> {
+    ///     orders: Factory<'cx, &'static Orders<'static>>,
+    /// }
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_with::<&Orders>(Cached::new(Injector::<Orders>::default()));
+    /// cx.bind_with::<&Customers>(Cached::new(Injector::<Customers>::default()));
+    ///
+    /// assert!(cx.validate().is_ok());
+    /// ```
+    ///
+    /// # Fails
+    /// This function will fail if any declared dependency is not bound, or if there are cycles
+    /// between the services. All of the problems found are returned.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        // The dependencies of each binding that are bound
+        let mut graph = HashMap::new();
+        let mut order = Vec::new();
+
+        for binding in self.bindings() {
+            let cx = self.provider_context(binding.owner, binding.provider);
+
+            let mut bound = Vec::new();
+            for dependency in binding.provider.dependencies() {
+                if cx.provider(dependency.key).is_some() {
+                    // Deferred dependencies are resolved after the provider has run
+                    if !dependency.is_deferred() {
+                        bound.push((dependency.key, dependency.service()));
+                    }
+                } else if !dependency.is_optional() {
+                    errors.push(ValidationError::MissingDependency(
+                        binding.provider.service,
+                        dependency.service(),
+                        dependency.name(),
+                    ));
+                }
+            }
+
+            graph.insert(binding.key, bound);
+            order.push((binding.key, binding.provider.service));
+        }
+
+        // Multi-bindings can not be depended on, so they can not be part of cycles
+        let mut multi_providers = Vec::new();
+        self.collect_all_multi_providers(&mut multi_providers);
+        for provider in multi_providers {
+            for dependency in provider.dependencies() {
+                if self.provider(dependency.key).is_none() && !dependency.is_optional() {
+                    errors.push(ValidationError::MissingDependency(
+                        provider.service,
+                        dependency.service(),
+                        dependency.name(),
+                    ));
+                }
+            }
+        }
+
+        let mut visited = HashSet::new();
+        for (key, service) in order {
+            find_cycles(
+                &graph,
+                (key, service),
+                &mut Vec::new(),
+                &mut visited,
+                &mut errors,
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// The graph of the services resolved so far through the root context of this context, or
+    /// through any of its sub-contexts, and the services each of their providers resolved
+    ///
+    /// A dependency is only recorded once a provider actually resolves it, so every service of
+    /// interest must be resolved at least once before the graph is complete. Dependencies resolved
+    /// in a scope are kept after it is dropped.
+    ///
+    /// ```
+    /// # use dfdi::{Context, Service};
+    /// # use std::any::type_name;
+    /// #[derive(Service)]
+    /// struct Config;
+    ///
+    /// #[derive(Service)]
+    /// struct Database;
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_named("primary", dfdi::provider_fn::<Config>(|_cx, _arg| Config));
+    /// cx.bind_fn::<Database>(|cx, _arg| {
+    ///     cx.resolve_named::<Config>("primary");
+    ///     Database
+    /// });
+    ///
+    /// let scope = cx.scoped();
+    /// scope.resolve::<Database>();
+    /// drop(scope);
+    ///
+    /// let config = format!("{} (primary)", type_name::<Config>());
+    /// let db = type_name::<Database>();
+    /// assert_eq!(
+    ///     cx.dependency_graph().to_json(),
+    ///     format!(r#"{{"nodes":["{config}","{db}"],"edges":[["{db}","{config}"]]}}"#),
+    /// );
+    /// ```
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        self.graph()
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Dispose of the instances owned by this context, in the reverse order they were created in,
+    /// and drop it
+    ///
+    /// Dropping a context disposes of its instances as well, but ignores any errors.
+    ///
+    /// # Fails
+    /// This function will fail if any instance fails to be disposed of. The remaining instances are
+    /// still disposed of.
+    pub fn shutdown(mut self) -> Result<(), ShutdownError> {
+        let errors = self.instances.dispose();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ShutdownError::DisposeFailed(errors))
+        }
+    }
+
+    /// Bind a provider for the service `S` to the key
+    fn bind_key<S: Service + ?Sized>(
+        &mut self,
+        key: Key,
+        provider: DynProvider,
+    ) -> Result<(), BindError> {
+        if self.provider(key).is_some() {
+            return Err(BindError::ServiceBound(type_name::<S>(), key.name));
+        }
+
+        self.providers.insert(key, Some(provider));
+        Ok(())
+    }
+
+    /// Whether a module was installed in this context or one of its parents
+    fn is_installed(&self, id: TypeId) -> bool {
+        self.modules.contains(&id) || self.parent.map_or(false, |parent| parent.is_installed(id))
+    }
+
+    /// Bind a provider that does not borrow from the context for any particular lifetime to the key
+    #[track_caller]
+    pub(crate) fn bind_static<S, P>(&mut self, key: Key, provider: P) -> Result<(), BindError>
+    where
+        S: Service,
+        P: for<'cx> Provider<'cx, S>,
+    {
+        self.bind_key::<S>(key, DynProvider::new(provider))
+    }
+
+    /// Bind a provider with a lifetime to the service `&S`
+    #[track_caller]
+    fn bind_lifetime<'cx, S>(
+        &'cx mut self,
+        provider: WithLifetime<impl Provider<'cx, S>>,
+    ) -> Result<(), BindError>
+    where
+        S: Service + ?Sized,
+        S::Output<'cx>: Send + Sync,
+    {
+        self.bind_key::<&'static S>(Key::of::<&'static S>(None), DynProvider::new(provider))
+    }
+
+    /// Remove the provider bound to the key of a service `S`
+    fn unbind_key<S: Service>(&mut self, key: Key) -> Result<(), UnbindError> {
+        if self.provider(key).is_none() {
+            return Err(UnbindError::ServiceUnbound(type_name::<S>(), key.name));
+        }
+
+        // A provider bound by a parent context can not be removed from here, so it has to be hidden
+        // instead
+        if self
+            .parent
+            .and_then(|parent| parent.provider(key))
+            .is_some()
+        {
+            self.providers.insert(key, None);
+        } else {
+            self.providers.remove(&key);
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the service `S` using the provider bound to the key
+    pub(crate) fn resolve_key<S: Service>(
+        &self,
+        key: Key,
+        arg: S::Argument<'_>,
+    ) -> Result<S::Output<'_>, ResolveError> {
+        let (owner, provider) = self
+            .binding(key)
+            .ok_or_else(|| ResolveError::ServiceUnbound(type_name::<S>(), key.name))?;
+
+        // SAFETY:
+        // - We know that the provider was created for the service `S`, since it was bound to a key
+        //   with its `TypeId`
+        unsafe {
+            self.provider_context(owner, provider)
+                .provide::<S>(key, provider, arg)
+        }
+    }
+
+    /// Run a provider for the service `S`, keeping track of the key it was bound to while it is
+    /// being resolved
+    ///
+    /// # Fails
+    /// If the provider fails, or if the key is already being resolved on this thread, which means
+    /// that there is a dependency cycle.
+    ///
+    /// SAFETY:
+    /// - The provider was created for the service `S`
+    unsafe fn provide<'cx, S: Service>(
+        &'cx self,
+        key: Key,
+        provider: &'cx DynProvider,
+        arg: S::Argument<'_>,
+    ) -> Result<S::Output<'cx>, ResolveError> {
+        if provider.is_async {
+            return Err(ResolveError::AsyncOnly(type_name::<S>()));
+        }
+
+        let (_guard, dependent) = StackGuard::push(key, type_name::<S>())?;
+        self.record::<S>(key, provider, dependent);
+
+        provider
+            .provide::<S>(self, arg)
+            .map_err(provider_error::<S>)
+    }
+
+    /// Record a resolution of the service `S` through the provider bound to the key, by the
+    /// provider of `dependent` if there is one
+    fn record<S: Service>(&self, key: Key, provider: &DynProvider, dependent: Option<Frame>) {
+        // Note: Each provider remembers the dependents it has already recorded, so that resolutions
+        // only need to lock the graph shared by all contexts the first time they are seen
+        if !provider.dependents.insert(dependent.map(|(key, _)| key)) {
+            return;
+        }
+
+        let dependent = dependent.map(|(key, service)| ServiceNode {
+            service,
+            name: key.name,
+        });
+        let node = ServiceNode {
+            service: type_name::<S>(),
+            name: key.name,
+        };
+        self.graph()
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .record(dependent, node);
+    }
+
+    /// The bindings visible from this context, sorted by service type name and name
+    fn bindings(&self) -> Vec<Binding<'_>> {
+        let mut bindings = Vec::new();
+        self.collect_bindings(&mut HashSet::new(), false, &mut bindings);
+        bindings.sort_by_key(|binding| (binding.provider.service, binding.key.name));
+        bindings
+    }
+
+    /// Collect the bindings of this context and its parents, skipping the keys that were already
+    /// seen in a sub-context
+    fn collect_bindings<'a>(
+        &'a self,
+        seen: &mut HashSet<Key>,
+        inherited: bool,
+        bindings: &mut Vec<Binding<'a>>,
+    ) {
+        for (key, provider) in &self.providers {
+            if !seen.insert(*key) {
+                continue;
+            }
+
+            if let Some(provider) = provider {
+                bindings.push(Binding {
+                    key: *key,
+                    owner: self,
+                    provider,
+                    inherited,
+                });
+            }
+        }
+
+        if let Some(parent) = self.parent {
+            parent.collect_bindings(seen, true, bindings);
+        }
+    }
+
+    /// Collect every provider added through `bind_many` in this context and its parents
+    fn collect_all_multi_providers<'a>(&'a self, providers: &mut Vec<&'a DynProvider>) {
+        if let Some(parent) = self.parent {
+            parent.collect_all_multi_providers(providers);
+        }
+
+        providers.extend(self.multi_providers.values().flatten());
+    }
+
+    /// Collect the providers added for a service in this context and its parents, in order, along
+    /// with the context they were added to
+    fn collect_multi_providers<'a>(
+        &'a self,
+        id: TypeId,
+        providers: &mut Vec<(&'a Context<'a>, &'a DynProvider)>,
+    ) {
+        if let Some(parent) = self.parent {
+            parent.collect_multi_providers(id, providers);
+        }
+
+        if let Some(own) = self.multi_providers.get(&id) {
+            providers.extend(own.iter().map(|provider| (self as &Context<'_>, provider)));
+        }
+    }
+
+    /// Find the provider bound to a key, either in this context or one of its parents
+    fn provider(&self, key: Key) -> Option<&DynProvider> {
+        self.binding(key).map(|(_, provider)| provider)
+    }
+
+    /// Find the provider bound to a key, along with the context it is bound in
+    fn binding(&self, key: Key) -> Option<(&Context<'_>, &DynProvider)> {
+        match self.providers.get(&key) {
+            Some(provider) => provider
+                .as_ref()
+                .map(|provider| (self as &Context<'_>, provider)),
+            None => self.parent?.binding(key),
+        }
+    }
+
+    /// The context a provider bound in `owner` should be run with, when resolving from this one
+    ///
+    /// Singletons, such as providers caching their output, are always run with the context they
+    /// are bound in, since their output outlives the sub-contexts it is resolved from.
+    #[inline(always)]
+    fn provider_context<'a>(
+        &'a self,
+        owner: &'a Context<'a>,
+        provider: &DynProvider,
+    ) -> &'a Context<'a> {
+        if provider.singleton {
+            owner
+        } else {
+            self
+        }
+    }
+
+    /// The dependency graph of the root of the chain of parents of this context
+    fn graph(&self) -> &Mutex<DependencyGraph> {
+        match (&self.graph, self.parent) {
+            (Some(graph), _) => graph,
+            (None, Some(parent)) => parent.graph(),
+            (None, None) => unreachable!("root contexts always have a dependency graph"),
+        }
+    }
+
+    /// The instances created in this context by providers bound with a [`Lifetime`]
+    #[inline(always)]
+    pub(crate) fn instances(&self) -> &Instances {
+        &self.instances
+    }
+}
+
+/// A provider bound to a key, along with the context it is bound in
+struct Binding<'a> {
+    key: Key,
+    owner: &'a Context<'a>,
+    provider: &'a DynProvider,
+
+    /// Whether the binding was found in a parent of the context it was looked up from
+    inherited: bool,
+}
+
+/// Find the cycles reachable from a service in a graph of the dependencies of each key, adding
+/// them to the errors
+///
+/// Each service is only visited once, so each cycle is only reported once.
+fn find_cycles(
+    graph: &HashMap<Key, Vec<(Key, &'static str)>>,
+    (key, service): (Key, &'static str),
+    path: &mut Vec<(Key, &'static str)>,
+    visited: &mut HashSet<Key>,
+    errors: &mut Vec<ValidationError>,
+) {
+    if let Some(start) = path.iter().position(|(k, _)| *k == key) {
+        let cycle = path[start..]
+            .iter()
+            .map(|(_, service)| *service)
+            .chain([service])
+            .collect();
+        errors.push(ValidationError::Cycle(cycle));
+        return;
+    }
+
+    if !visited.insert(key) {
+        return;
+    }
+
+    path.push((key, service));
+    for dependency in graph.get(&key).into_iter().flatten() {
+        find_cycles(graph, *dependency, path, visited, errors);
+    }
+    path.pop();
+}
+
+/// A provider wrapped by a decorator
+struct Decorated<'p, D> {
+    inner: Inner<'p>,
+    decorator: D,
+}
+
+/// The provider wrapped by a decorator
+enum Inner<'p> {
+    /// A provider that was bound in the same context as the decorator
+    Owned(DynProvider),
+
+    /// A provider bound in a parent context, along with that context
+    Inherited(&'p Context<'p>, &'p DynProvider),
+}
+
+// SAFETY:
+// - All providers must be Send
+unsafe impl<D: Send> Send for Decorated<'_, D> {}
+
+// SAFETY:
+// - All providers must be Sync
+unsafe impl<D: Sync> Sync for Decorated<'_, D> {}
+
+impl<'p, 'cx, S, D> Provider<'cx, S> for Decorated<'p, D>
+where
+    'p: 'cx,
+    S: Service,
+    D: Fn(
+            &'cx Context,
+            S::Argument<'_>,
+            &dyn Fn(S::Argument<'_>) -> Result<S::Output<'cx>, Box<dyn Error + Send + Sync>>,
+        ) -> Result<S::Output<'cx>, Box<dyn Error + Send + Sync>>
+        + Send
+        + Sync
+        + 'cx,
+{
+    fn provide(&'cx self, cx: &'cx Context, arg: S::Argument<'_>) -> S::Output<'cx> {
+        match self.try_provide(cx, arg) {
+            Ok(s) => s,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    fn try_provide(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'_>,
+    ) -> Result<S::Output<'cx>, Box<dyn Error + Send + Sync>> {
+        // Singletons bound by a parent context must still be created in that context
+        let (inner_cx, inner): (&'cx Context, &'cx DynProvider) = match &self.inner {
+            Inner::Owned(provider) => (cx, provider),
+            Inner::Inherited(owner, provider) if provider.singleton => (owner, provider),
+            Inner::Inherited(_, provider) => (cx, provider),
+        };
+
+        let next = |arg: S::Argument<'_>| {
+            if inner.is_async {
+                return Err(ResolveError::AsyncOnly(type_name::<S>()).into());
+            }
+
+            // SAFETY:
+            // - The provider was created for the service `S`, since it was bound to a key with its
+            //   `TypeId`
+            // - We just checked that it is not an async provider
+            unsafe { inner.provide::<S>(inner_cx, arg) }
+        };
+
+        (self.decorator)(cx, arg, &next)
+    }
+
+    fn dependencies(&self) -> Vec<Dependency> {
+        match &self.inner {
+            Inner::Owned(provider) => provider.dependencies(),
+            Inner::Inherited(_, provider) => provider.dependencies(),
+        }
+    }
+
+    #[inline(always)]
+    fn is_singleton(&self) -> bool {
+        matches!(&self.inner, Inner::Owned(provider) if provider.singleton)
+    }
+}
+
+/// Convert an error returned by a provider of the service `S` into a [`ResolveError`]
+fn provider_error<S: Service>(err: Box<dyn Error + Send + Sync>) -> ResolveError {
+    match err.downcast::<ResolveError>() {
+        Ok(err) => ResolveError::Dependency(type_name::<S>(), err),
+        Err(err) => ResolveError::ProviderFailed(type_name::<S>(), err),
+    }
+}
+
+impl Debug for Context<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Context")
+            .field("services", &self.services().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Context<'_> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A description of a service binding, as returned by [`Context::services`]
+#[derive(Clone, Copy, Debug)]
+pub struct ServiceDescriptor {
+    /// The type name of the service
+    pub service: &'static str,
+
+    /// The name the service is bound under
+    pub name: Option<&'static str>,
+
+    /// The type name of the provider
+    pub provider: &'static str,
+
+    /// Where the provider was bound
+    pub location: &'static Location<'static>,
+
+    /// Whether the service is bound in a parent context, rather than the context it was listed from
+    pub inherited: bool,
+}
+
+/// The key a provider is bound to: The `TypeId` of a service, and an optional name
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Key {
+    service: TypeId,
+    pub(crate) name: Option<&'static str>,
+}
+
+impl Key {
+    #[inline(always)]
+    pub(crate) fn of<S: Service + ?Sized>(name: Option<&'static str>) -> Self {
+        Self {
+            service: TypeId::of::<S>(),
+            name,
+        }
+    }
+
+    /// The key identifying the multi-binding of the service `S` during resolution
+    #[inline(always)]
+    fn many<S: Service>() -> Self {
+        /// Tells multi-bindings apart from regular bindings of the same service
+        struct Many<S>(PhantomData<S>);
+
+        Self {
+            service: TypeId::of::<Many<S>>(),
+            name: None,
+        }
     }
 }
 
@@ -256,38 +2031,123 @@ struct DynProvider {
     /// Type-erased pointer to the underlying provider data
     this: NonNull<()>,
 
-    /// Type-erased function pointer to the provider's `provide` implementation
+    /// Type-erased function pointer to the provider's `try_provide` implementation, or its
+    /// `try_provide_async` implementation if this is an async provider
     provide_fn: NonNull<()>,
 
+    /// Whether the provider is an [`AsyncProvider`]
+    is_async: bool,
+
+    /// Whether the provider must be run with the context it is bound in, rather than the one a
+    /// service is resolved from
+    singleton: bool,
+
     /// Pointer to the provider's `drop` implementation
     //
     // SAFETY:
     // - Must only be called with a valid `self.this` pointer
-    drop_fn: Option<unsafe fn(*mut ())>,
+    drop_fn: unsafe fn(*mut ()),
+
+    /// The type name of the service the provider was created for
+    service: &'static str,
+
+    /// The type name of the provider
+    provider: &'static str,
+
+    /// Where the provider was bound
+    location: &'static Location<'static>,
+
+    /// Pointer to the provider's `dependencies` implementation
+    //
+    // SAFETY:
+    // - Must only be called with a valid `self.this` pointer
+    dependencies_fn: unsafe fn(*const ()) -> Vec<Dependency>,
+
+    /// The dependents of the provider that are already recorded in the dependency graph
+    dependents: Dependents,
 }
 
 impl DynProvider {
     /// Create a `DynProvider` for the service `S`
-    ///
-    /// SAFETY:
-    /// - This instance must live as long as all of its clones
-    unsafe fn new<'cx, S, P>(provider: P) -> Self
+    #[track_caller]
+    fn new<'cx, S, P>(provider: P) -> Self
     where
-        S: Service,
+        S: Service + ?Sized,
         P: Provider<'cx, S>,
     {
-        unsafe fn drop_provider<P>(this: *mut ()) {
-            std::mem::drop(Box::from_raw(this as *mut P));
+        // Get the P::try_provide function pointer and store a type-erased version of it
+        //
+        // SAFETY:
+        // - fn pointers are always non-null
+        let provide_fn = unsafe { NonNull::new_unchecked(P::try_provide as fn(_, _, _) -> _ as _) };
+
+        unsafe fn dependencies<'cx, S, P>(this: *const ()) -> Vec<Dependency>
+        where
+            S: Service + ?Sized,
+            P: Provider<'cx, S>,
+        {
+            (*(this as *const P)).dependencies()
         }
 
-        // Create a pointer to a specialized `drop` function and store it.
-        let drop_fn = Some(drop_provider::<P> as _);
+        let singleton = provider.is_singleton();
+        Self::from_parts(
+            provider,
+            provide_fn,
+            false,
+            singleton,
+            type_name::<S>(),
+            dependencies::<S, P>,
+        )
+    }
 
-        // Get the P::provide function pointer and store a type-erased version of it
+    /// Create a `DynProvider` for the service `S` from an async provider
+    #[track_caller]
+    fn new_async<'cx, S, P>(provider: P) -> Self
+    where
+        S: Service,
+        P: AsyncProvider<'cx, S>,
+    {
+        // Get the P::try_provide_async function pointer and store a type-erased version of it
         //
         // SAFETY:
         // - fn pointers are always non-null
-        let provide_fn = unsafe { NonNull::new_unchecked(P::provide as fn(_, _, _) -> _ as _) };
+        let provide_fn =
+            unsafe { NonNull::new_unchecked(P::try_provide_async as fn(_, _, _) -> _ as _) };
+
+        unsafe fn dependencies<'cx, S, P>(this: *const ()) -> Vec<Dependency>
+        where
+            S: Service,
+            P: AsyncProvider<'cx, S>,
+        {
+            (*(this as *const P)).dependencies()
+        }
+
+        let singleton = provider.is_singleton();
+        Self::from_parts(
+            provider,
+            provide_fn,
+            true,
+            singleton,
+            type_name::<S>(),
+            dependencies::<S, P>,
+        )
+    }
+
+    #[track_caller]
+    fn from_parts<P>(
+        provider: P,
+        provide_fn: NonNull<()>,
+        is_async: bool,
+        singleton: bool,
+        service: &'static str,
+        dependencies_fn: unsafe fn(*const ()) -> Vec<Dependency>,
+    ) -> Self {
+        unsafe fn drop_provider<P>(this: *mut ()) {
+            std::mem::drop(Box::from_raw(this as *mut P));
+        }
+
+        // Create a pointer to a specialized `drop` function and store it.
+        let drop_fn = drop_provider::<P> as _;
 
         // Create the `this` pointer.
         //
@@ -297,16 +2157,35 @@ impl DynProvider {
 
         Self {
             this,
-            drop_fn,
             provide_fn,
+            is_async,
+            singleton,
+            drop_fn,
+            service,
+            provider: type_name::<P>(),
+            location: Location::caller(),
+            dependencies_fn,
+            dependents: Dependents::default(),
         }
     }
 
+    /// The services the provider declares it resolves
+    fn dependencies(&self) -> Vec<Dependency> {
+        // SAFETY:
+        // - `dependencies_fn` can only be called with `self.this`, which it is.
+        unsafe { (self.dependencies_fn)(self.this.as_ptr()) }
+    }
+
     /// Run the provider
     ///
     /// SAFETY:
     /// - The `DynProvider` was created for the service `S`
-    unsafe fn provide<'cx, S>(&'cx self, cx: &'cx Context, arg: S::Argument<'_>) -> S::Output<'cx>
+    /// - The `DynProvider` was not created from an async provider
+    unsafe fn provide<'cx, S>(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'_>,
+    ) -> Result<S::Output<'cx>, Box<dyn Error + Send + Sync>>
     where
         S: Service,
     {
@@ -315,26 +2194,58 @@ impl DynProvider {
 
         provide_fn(this, cx, arg)
     }
+
+    /// Run the async provider
+    ///
+    /// SAFETY:
+    /// - The `DynProvider` was created for the service `S`
+    /// - The `DynProvider` was created from an async provider
+    unsafe fn provide_async<'cx, S>(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'cx>,
+    ) -> BoxFuture<'cx, Result<S::Output<'cx>, Box<dyn Error + Send + Sync>>>
+    where
+        S: Service,
+    {
+        let this = self.this.as_ptr() as *const ();
+        let provide_fn: ProvideAsyncFn<'cx, S> = std::mem::transmute(self.provide_fn);
+
+        provide_fn(this, cx, arg)
+    }
 }
 
-impl Clone for DynProvider {
-    fn clone(&self) -> Self {
-        Self {
-            this: self.this,
-            provide_fn: self.provide_fn,
-            drop_fn: None, // drop should only run on the original instance
+/// The keys of the services whose providers resolved a provider, or `None` if it was resolved
+/// directly
+///
+/// This is an append-only list, so that looking up a dependent does not need any locking.
+#[derive(Default)]
+struct Dependents(OnceCell<Box<(Option<Key>, Dependents)>>);
+
+impl Dependents {
+    /// Add a dependent to the list, returning whether it was not in it yet
+    fn insert(&self, dependent: Option<Key>) -> bool {
+        let mut list = self;
+        loop {
+            let mut inserted = false;
+            let (key, next) = &**list.0.get_or_init(|| {
+                inserted = true;
+                Box::new((dependent, Dependents::default()))
+            });
+
+            if *key == dependent {
+                return inserted;
+            }
+            list = next;
         }
     }
 }
 
 impl Drop for DynProvider {
     fn drop(&mut self) {
-        if let Some(drop_fn) = self.drop_fn {
-            // SAFETY:
-            // - `drop_fn` can only be called with `self.this`, which it is.
-            // - We know drop has not been called because of the safety guarantees on new(), which
-            //   means that `self.this` points to valid memory.
-            unsafe { (drop_fn)(self.this.as_ptr()) }
-        }
+        // SAFETY:
+        // - `drop_fn` can only be called with `self.this`, which it is.
+        // - `DynProvider` owns the provider, so `self.this` points to valid memory until now.
+        unsafe { (self.drop_fn)(self.this.as_ptr()) }
     }
 }
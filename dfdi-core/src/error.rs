@@ -7,8 +7,17 @@ use std::{
 #[non_exhaustive]
 #[derive(Debug)]
 pub enum BindError {
-    /// The service has already been bound to another provider
-    ServiceBound(&'static str),
+    /// The service, with an optional name, has already been bound to another provider
+    ServiceBound(&'static str, Option<&'static str>),
+
+    /// The service, with an optional name, is not bound to a provider, so it can not be decorated
+    ServiceUnbound(&'static str, Option<&'static str>),
+
+    /// Binding a service failed while installing the module
+    Module(&'static str, Box<BindError>),
+
+    /// The service can not be disposed of by the context, since its instances are transient
+    TransientDisposable(&'static str),
 }
 
 impl Error for BindError {}
@@ -16,8 +25,29 @@ impl Error for BindError {}
 impl Display for BindError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::ServiceBound(service) => {
-                write!(f, "service `{service}` is already bound to a provider")
+            Self::ServiceBound(service, name) => {
+                write!(
+                    f,
+                    "service {} is already bound to a provider",
+                    ServiceName(service, *name)
+                )
+            }
+            Self::ServiceUnbound(service, name) => {
+                write!(
+                    f,
+                    "service {} is not bound to a provider",
+                    ServiceName(service, *name)
+                )
+            }
+            Self::Module(module, source) => {
+                write!(f, "failed to install module `{module}`: {source}")
+            }
+            Self::TransientDisposable(service) => {
+                write!(
+                    f,
+                    "service `{service}` is transient, so its instances can not be disposed of by \
+                     the context"
+                )
             }
         }
     }
@@ -27,8 +57,8 @@ impl Display for BindError {
 #[non_exhaustive]
 #[derive(Debug)]
 pub enum UnbindError {
-    /// The service is not bound to a provider
-    ServiceUnbound(&'static str),
+    /// The service, with an optional name, is not bound to a provider
+    ServiceUnbound(&'static str, Option<&'static str>),
 }
 
 impl Error for UnbindError {}
@@ -36,9 +66,177 @@ impl Error for UnbindError {}
 impl Display for UnbindError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            UnbindError::ServiceUnbound(service) => {
-                write!(f, "service `{service}` is not bound to a provider")
+            UnbindError::ServiceUnbound(service, name) => {
+                write!(
+                    f,
+                    "service {} is not bound to a provider",
+                    ServiceName(service, *name)
+                )
+            }
+        }
+    }
+}
+
+/// Error while resolving a service
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum ResolveError {
+    /// The service, with an optional name, is not bound to a provider
+    ServiceUnbound(&'static str, Option<&'static str>),
+
+    /// The provider of the service failed
+    ProviderFailed(&'static str, Box<dyn Error + Send + Sync>),
+
+    /// Resolving a dependency of the service failed
+    Dependency(&'static str, Box<ResolveError>),
+
+    /// The service is bound to an async provider, so it can only be resolved asynchronously
+    AsyncOnly(&'static str),
+
+    /// The service depends on itself. Holds the services that form the cycle, starting and ending
+    /// with the same service.
+    Cycle(Vec<&'static str>),
+}
+
+impl ResolveError {
+    /// The chain of services that was being resolved when the error occured, from the outermost
+    /// service to the one that failed
+    pub fn chain(&self) -> Vec<&'static str> {
+        let mut chain = Vec::new();
+        let mut err = self;
+        loop {
+            match err {
+                Self::ServiceUnbound(service, _)
+                | Self::ProviderFailed(service, _)
+                | Self::AsyncOnly(service) => {
+                    chain.push(*service);
+                    return chain;
+                }
+                Self::Dependency(service, source) => {
+                    chain.push(*service);
+                    err = source;
+                }
+                Self::Cycle(cycle) => {
+                    chain.extend(cycle.last());
+                    return chain;
+                }
+            }
+        }
+    }
+
+    /// The error that caused resolution to fail, skipping over any dependency chain
+    pub fn root_cause(&self) -> &ResolveError {
+        match self {
+            Self::Dependency(_, source) => source.root_cause(),
+            err => err,
+        }
+    }
+}
+
+impl Error for ResolveError {}
+
+impl Display for ResolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ServiceUnbound(service, name) => {
+                write!(
+                    f,
+                    "service {} is not bound to a provider",
+                    ServiceName(service, *name)
+                )
+            }
+            Self::ProviderFailed(service, source) => {
+                write!(f, "provider for service `{service}` failed: {source}")
+            }
+            Self::AsyncOnly(service) => {
+                write!(
+                    f,
+                    "service `{service}` is bound to an async provider and must be resolved \
+                     asynchronously"
+                )
+            }
+            Self::Cycle(cycle) => {
+                write!(f, "dependency cycle detected: `{}`", cycle.join(" -> "))
             }
+            Self::Dependency(..) => {
+                write!(
+                    f,
+                    "failed to resolve `{}`: {}",
+                    self.chain().join(" -> "),
+                    self.root_cause()
+                )
+            }
+        }
+    }
+}
+
+/// Error while shutting down a context
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum ShutdownError {
+    /// Disposing of instances failed. Holds the service of each instance along with the error, in
+    /// the order the instances were disposed of.
+    DisposeFailed(Vec<(&'static str, Box<dyn Error + Send + Sync>)>),
+}
+
+impl Error for ShutdownError {}
+
+impl Display for ShutdownError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DisposeFailed(errors) => {
+                write!(f, "failed to dispose of instances: ")?;
+                for (i, (service, source)) in errors.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "`{service}`: {source}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A problem found while validating a context
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum ValidationError {
+    /// The service depends on a service, with an optional name, that is not bound to a provider
+    MissingDependency(&'static str, &'static str, Option<&'static str>),
+
+    /// Services depend on each other. Holds the services that form the cycle, starting and ending
+    /// with the same service.
+    Cycle(Vec<&'static str>),
+}
+
+impl Error for ValidationError {}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingDependency(service, dependency, name) => {
+                write!(
+                    f,
+                    "service `{service}` depends on service {}, which is not bound to a provider",
+                    ServiceName(dependency, *name)
+                )
+            }
+            Self::Cycle(cycle) => {
+                write!(f, "dependency cycle detected: `{}`", cycle.join(" -> "))
+            }
+        }
+    }
+}
+
+/// Formats a service along with its name, if it has one
+struct ServiceName<'a>(&'a str, Option<&'a str>);
+
+impl Display for ServiceName<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.1 {
+            Some(name) => write!(f, "`{}` named `{name}`", self.0),
+            None => write!(f, "`{}`", self.0),
         }
     }
 }
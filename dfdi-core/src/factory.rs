@@ -0,0 +1,119 @@
+use std::marker::PhantomData;
+
+use crate::{Context, Dependency, ResolveError, Service};
+
+/// Factory service
+///
+/// Resolving `Factory<S>` returns a handle which can be called any number of times, with a
+/// different service argument each time. Each call runs the provider bound to `S` in the context
+/// the factory was resolved from. This lets consumers create services from arguments that are only
+/// known at runtime.
+///
+/// ```
+/// # use dfdi::{Context, Factory, Service};
+/// #[derive(Service)]
+/// #[service(u64 -> Self)]
+/// struct Session(u64);
+///
+/// let mut cx = Context::new();
+/// cx.bind_fn::<Session>(|_cx, user| Session(user));
+///
+/// let sessions = cx.resolve::<Factory<Session>>();
+/// assert_eq!(sessions.call(1).0, 1);
+/// assert_eq!(sessions.call(2).0, 2);
+/// ```
+///
+/// Like other service types, `S` has all of its lifetimes set to `'static`. If it is bound under a
+/// name, the factory must be resolved under that same name.
+pub struct Factory<'cx, S: Service> {
+    cx: &'cx Context<'cx>,
+    name: Option<&'static str>,
+    service: PhantomData<fn() -> S>,
+}
+
+impl<'cx, S: Service> Factory<'cx, S> {
+    /// Create the service from the given argument
+    ///
+    /// # Panics
+    /// If the service resolution fails. See [`try_call`](Self::try_call) for a fallible version of
+    /// this function.
+    #[track_caller]
+    pub fn call(&self, arg: S::Argument<'_>) -> S::Output<'cx> {
+        match self.name {
+            Some(name) => self.cx.resolve_named_with::<S>(name, arg),
+            None => self.cx.resolve_with::<S>(arg),
+        }
+    }
+
+    /// Try to create the service from the given argument
+    ///
+    /// # Fails
+    /// This function will fail if the service resolution fails.
+    ///
+    /// See [`call`](Self::call) for the panicking version of this function.
+    pub fn try_call(&self, arg: S::Argument<'_>) -> Result<S::Output<'cx>, ResolveError> {
+        match self.name {
+            Some(name) => self.cx.try_resolve_named_with::<S>(name, arg),
+            None => self.cx.try_resolve_with::<S>(arg),
+        }
+    }
+
+    /// Get a closure that creates the service from its argument, for consumers expecting a
+    /// function rather than a factory
+    ///
+    /// ```
+    /// # use dfdi::{Context, Factory, Service};
+    /// #[derive(Service)]
+    /// #[service(u64 -> Self)]
+    /// struct Session(u64);
+    ///
+    /// fn open_all(users: &[u64], open: impl Fn(u64) -> Session) -> Vec<Session> {
+    ///     users.iter().map(|&user| open(user)).collect()
+    /// }
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_fn::<Session>(|_cx, user| Session(user));
+    ///
+    /// let sessions = cx.resolve::<Factory<Session>>();
+    /// assert_eq!(open_all(&[1, 2], sessions.as_fn())[1].0, 2);
+    /// ```
+    ///
+    /// The closure panics if the service resolution fails, same as [`call`](Self::call).
+    #[inline(always)]
+    pub fn as_fn(&self) -> impl Fn(S::Argument<'_>) -> S::Output<'cx> + Send + Sync + 'cx {
+        let factory = *self;
+        move |arg| factory.call(arg)
+    }
+}
+
+impl<S: Service> Clone for Factory<'_, S> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S: Service> Copy for Factory<'_, S> {}
+
+impl<S: Service> Service for Factory<'static, S> {
+    type Output<'cx> = Factory<'cx, S>;
+    type Argument<'arg> = ();
+
+    #[inline(always)]
+    fn resolve_in<'cx>(
+        cx: &'cx Context,
+        name: Option<&'static str>,
+        _arg: (),
+    ) -> Result<Factory<'cx, S>, ResolveError> {
+        Ok(Factory {
+            cx,
+            name,
+            service: PhantomData,
+        })
+    }
+
+    #[inline(always)]
+    fn dependency(name: Option<&'static str>) -> Dependency {
+        S::dependency(name).deferred()
+    }
+}
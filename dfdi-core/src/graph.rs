@@ -0,0 +1,182 @@
+use std::{
+    collections::BTreeSet,
+    fmt::{Display, Write},
+};
+
+/// A service in a [`DependencyGraph`]: The type name of the service, and the name it is bound
+/// under, if any
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ServiceNode {
+    /// The type name of the service
+    pub service: &'static str,
+
+    /// The name the service is bound under
+    pub name: Option<&'static str>,
+}
+
+impl Display for ServiceNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "{} ({name})", self.service),
+            None => write!(f, "{}", self.service),
+        }
+    }
+}
+
+/// The services resolved through a [`Context`](crate::Context), and the services each of their
+/// providers resolved in turn
+///
+/// Nodes and edges are kept sorted, so that exported graphs can be compared.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DependencyGraph {
+    nodes: BTreeSet<ServiceNode>,
+    edges: BTreeSet<(ServiceNode, ServiceNode)>,
+}
+
+impl DependencyGraph {
+    /// The services in the graph
+    pub fn nodes(&self) -> impl Iterator<Item = ServiceNode> + '_ {
+        self.nodes.iter().copied()
+    }
+
+    /// The dependencies in the graph, from the dependent service to the service it depends on
+    pub fn edges(&self) -> impl Iterator<Item = (ServiceNode, ServiceNode)> + '_ {
+        self.edges.iter().copied()
+    }
+
+    /// Export the graph in the Graphviz DOT format
+    ///
+    /// Each node is labelled with the type name of its service, followed by the name it is bound
+    /// under in parentheses, if any. Quotes and backslashes are escaped.
+    ///
+    /// ```
+    /// # use dfdi::{provider_fn, Context, Service};
+    /// # use std::any::type_name;
+    /// #[derive(Service)]
+    /// struct Config;
+    ///
+    /// #[derive(Service)]
+    /// struct Database;
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_named(r#"C:\"app""#, provider_fn::<Config>(|_cx, _arg| Config));
+    /// cx.bind_fn::<Database>(|cx, _arg| {
+    ///     cx.resolve_named::<Config>(r#"C:\"app""#);
+    ///     Database
+    /// });
+    /// cx.resolve::<Database>();
+    ///
+    /// // The escaped label of each node
+    /// let config = format!(r#"{} (C:\\\"app\")"#, type_name::<Config>());
+    /// let db = type_name::<Database>();
+    /// assert_eq!(
+    ///     cx.dependency_graph().to_dot(),
+    ///     format!(
+    ///         r#"digraph dependencies {{
+    ///     "{config}";
+    ///     "{db}";
+    ///     "{db}" -> "{config}";
+    /// }}
+    /// "#
+    ///     ),
+    /// );
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph dependencies {\n");
+        for node in &self.nodes {
+            let _ = writeln!(dot, "    \"{}\";", DotEscape(node));
+        }
+        for (from, to) in &self.edges {
+            let _ = writeln!(dot, "    \"{}\" -> \"{}\";", DotEscape(from), DotEscape(to));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Export the graph as JSON, with the format `{"nodes": [...], "edges": [[from, to], ...]}`
+    ///
+    /// Nodes are labelled the same as with [`to_dot`](Self::to_dot), as JSON strings.
+    ///
+    /// ```
+    /// # use dfdi::{provider_fn, Context, Service};
+    /// # use std::any::type_name;
+    /// #[derive(Service)]
+    /// struct Config;
+    ///
+    /// #[derive(Service)]
+    /// struct Database;
+    ///
+    /// let mut cx = Context::new();
+    /// cx.bind_named(r#"C:\"app""#, provider_fn::<Config>(|_cx, _arg| Config));
+    /// cx.bind_fn::<Database>(|cx, _arg| {
+    ///     cx.resolve_named::<Config>(r#"C:\"app""#);
+    ///     Database
+    /// });
+    /// cx.resolve::<Database>();
+    ///
+    /// // The escaped label of each node
+    /// let config = format!(r#"{} (C:\\\"app\")"#, type_name::<Config>());
+    /// let db = type_name::<Database>();
+    /// assert_eq!(
+    ///     cx.dependency_graph().to_json(),
+    ///     format!(r#"{{"nodes":["{config}","{db}"],"edges":[["{db}","{config}"]]}}"#),
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"nodes\":[");
+        for (i, node) in self.nodes.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let _ = write!(json, "\"{}\"", JsonEscape(node));
+        }
+        json.push_str("],\"edges\":[");
+        for (i, (from, to)) in self.edges.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let _ = write!(json, "[\"{}\",\"{}\"]", JsonEscape(from), JsonEscape(to));
+        }
+        json.push_str("]}");
+        json
+    }
+
+    /// Record a resolution of the service `node`, by the provider of `dependent` if there is one
+    pub(crate) fn record(&mut self, dependent: Option<ServiceNode>, node: ServiceNode) {
+        self.nodes.insert(node);
+        if let Some(dependent) = dependent {
+            self.edges.insert((dependent, node));
+        }
+    }
+}
+
+/// Formats a node as the contents of a DOT string
+struct DotEscape<'a>(&'a ServiceNode);
+
+impl Display for DotEscape<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in self.0.to_string().chars() {
+            match c {
+                '"' | '\\' => write!(f, "\\{c}")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats a node as the contents of a JSON string
+struct JsonEscape<'a>(&'a ServiceNode);
+
+impl Display for JsonEscape<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in self.0.to_string().chars() {
+            match c {
+                '"' | '\\' => write!(f, "\\{c}")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,24 +1,162 @@
-use crate::{Context, Provider, Service};
+use std::{error::Error, future::Future, marker::PhantomData, sync::Arc};
+
+use once_cell::sync::OnceCell;
+
+use crate::{
+    AsyncProvider, BoxFuture, Context, Dependency, Provider, ResolveError, Service, Upcast,
+};
 
 /// Allow `Fn` functions to act as providers.
 impl<'cx, F, S> Provider<'cx, S> for F
 where
     F: Fn(&'cx Context, S::Argument<'_>) -> S::Output<'cx> + Send + Sync + 'cx,
-    S: Service,
+    S: Service + ?Sized,
 {
     fn provide(&'cx self, cx: &'cx Context, arg: S::Argument<'_>) -> S::Output<'cx> {
         (self)(cx, arg)
     }
 }
 
+/// Allow `Fn` functions returning futures to act as async providers.
+impl<'cx, F, Fut, S> AsyncProvider<'cx, S> for F
+where
+    F: Fn(&'cx Context, S::Argument<'cx>) -> Fut + Send + Sync + 'cx,
+    Fut: Future<Output = S::Output<'cx>> + Send + 'cx,
+    S: Service + ?Sized,
+{
+    fn provide_async(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'cx>,
+    ) -> BoxFuture<'cx, S::Output<'cx>> {
+        Box::pin((self)(cx, arg))
+    }
+}
+
+/// Provides a reference to a value that was upcast into a trait object service
+pub(crate) struct Upcasted<'cx, T: Service + ?Sized>(pub(crate) T::Output<'cx>);
+
+impl<'cx, T> Provider<'cx, &'static T> for Upcasted<'cx, T>
+where
+    T: Service + ?Sized,
+    T::Output<'cx>: Send + Sync,
+{
+    #[inline(always)]
+    fn provide(&'cx self, _cx: &'cx Context, _arg: T::Argument<'_>) -> &'cx T::Output<'cx> {
+        &self.0
+    }
+}
+
+/// Provides a reference to the output of a provider of the service `C`, upcast into a trait object
+/// service
+///
+/// The provider is only run the first time the service is resolved, with the default argument.
+pub(crate) struct UpcastedWith<'cx, T: Service + ?Sized, C: Service + ?Sized, P> {
+    provider: P,
+    cache: OnceCell<T::Output<'cx>>,
+    service: PhantomData<fn() -> C>,
+}
+
+impl<'cx, T: Service + ?Sized, C: Service + ?Sized, P> UpcastedWith<'cx, T, C, P> {
+    pub(crate) fn new(provider: P) -> Self {
+        Self {
+            provider,
+            cache: OnceCell::new(),
+            service: PhantomData,
+        }
+    }
+}
+
+impl<'cx, T, C, P> Provider<'cx, &'static T> for UpcastedWith<'cx, T, C, P>
+where
+    T: Upcast<C::Output<'cx>> + ?Sized,
+    T::Output<'cx>: Send + Sync,
+    C: Service + ?Sized,
+    C::Output<'cx>: 'cx,
+    C::Argument<'static>: Default,
+    P: Provider<'cx, C>,
+{
+    fn provide(&'cx self, cx: &'cx Context, arg: T::Argument<'_>) -> &'cx T::Output<'cx> {
+        match self.try_provide(cx, arg) {
+            Ok(s) => s,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    fn try_provide(
+        &'cx self,
+        cx: &'cx Context,
+        _arg: T::Argument<'_>,
+    ) -> Result<&'cx T::Output<'cx>, Box<dyn Error + Send + Sync>> {
+        self.cache.get_or_try_init(|| {
+            self.provider
+                .try_provide(cx, Default::default())
+                .map(T::upcast)
+        })
+    }
+
+    #[inline(always)]
+    fn dependencies(&self) -> Vec<Dependency> {
+        self.provider.dependencies()
+    }
+    #[inline(always)]
+    fn is_singleton(&self) -> bool {
+        true
+    }
+}
+
 // Common service types
 
-impl<S: Service> Service for &'static S {
+impl<S: Service + ?Sized> Service for &'static S {
     type Output<'cx> = &'cx S::Output<'cx>;
     type Argument<'arg> = S::Argument<'arg>;
 }
 
-impl<S: Service> Service for &'static mut S {
+impl<S: Service + ?Sized> Service for &'static mut S {
     type Output<'cx> = &'cx mut S::Output<'cx>;
     type Argument<'arg> = S::Argument<'arg>;
 }
+
+impl<S: Service + ?Sized> Service for Arc<S> {
+    type Output<'cx> = Arc<S::Output<'cx>>;
+    type Argument<'arg> = S::Argument<'arg>;
+}
+
+/// An optional service, which resolves to `None` instead of failing when the service `S` is not
+/// bound
+///
+/// ```
+/// # use dfdi::{Context, Service};
+/// #[derive(Service)]
+/// struct Tracer;
+///
+/// let mut cx = Context::new();
+/// assert!(cx.resolve::<Option<Tracer>>().is_none());
+///
+/// cx.bind_fn::<Tracer>(|_cx, _arg| Tracer);
+/// assert!(cx.resolve::<Option<Tracer>>().is_some());
+/// ```
+///
+/// Only a missing binding for `S` itself is turned into `None`. If `S` is bound but its provider
+/// fails, including because one of its own dependencies is unbound, resolution still fails.
+impl<S: Service> Service for Option<S> {
+    type Output<'cx> = Option<S::Output<'cx>>;
+    type Argument<'arg> = S::Argument<'arg>;
+
+    fn resolve_in<'cx>(
+        cx: &'cx Context,
+        name: Option<&'static str>,
+        arg: Self::Argument<'_>,
+    ) -> Result<Self::Output<'cx>, ResolveError> {
+        match S::resolve_in(cx, name, arg) {
+            Ok(s) => Ok(Some(s)),
+            Err(ResolveError::ServiceUnbound(..)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    #[inline(always)]
+    fn dependency(name: Option<&'static str>) -> Dependency {
+        S::dependency(name).optional()
+    }
+}
@@ -1,8 +1,17 @@
+mod builder;
 mod context;
 mod error;
+mod factory;
+mod graph;
 mod impls;
+mod lifetime;
+mod stack;
 mod traits;
 
+pub use builder::{Contains, ContextBuilder, HCons, HNil, Here, There};
 pub use context::*;
 pub use error::*;
+pub use factory::Factory;
+pub use graph::{DependencyGraph, ServiceNode};
+pub use lifetime::Lifetime;
 pub use traits::*;
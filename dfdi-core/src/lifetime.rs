@@ -0,0 +1,302 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    ptr::NonNull,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
+use once_cell::sync::OnceCell;
+
+use crate::{Context, Dependency, Dispose, Provider, Service};
+
+/// How the instances created by a provider are shared between resolutions of a service
+///
+/// See [`Context::bind_with_lifetime`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Lifetime {
+    /// A new instance is created every time the service is resolved
+    ///
+    /// Instances are handed out by value, through the service `S` itself rather than `&S`, so the
+    /// context does not keep them.
+    Transient,
+
+    /// A single instance is created, and shared by the context the service is bound in and all of
+    /// its sub-contexts
+    ///
+    /// The instance is always created in the context the service is bound in, so its dependencies
+    /// are resolved from there, and never from a sub-context.
+    Singleton,
+
+    /// A single instance is created for each context the service is resolved from
+    Scoped,
+}
+
+/// A provider creating a single instance of the service `S` for each [`Context`] it is run with
+///
+/// The instances are stored in the [`Context`] the provider is run with. Transient bindings do not
+/// need to store their instances, so they are bound without this provider.
+pub(crate) struct WithLifetime<P> {
+    provider: P,
+    lifetime: Lifetime,
+
+    /// Identifies the instances of this binding in a context
+    id: u64,
+
+    /// Disposes of the instances before they are dropped
+    disposer: Option<Disposer>,
+}
+
+impl<P> WithLifetime<P> {
+    pub(crate) fn new(lifetime: Lifetime, provider: P) -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        Self {
+            provider,
+            lifetime,
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            disposer: None,
+        }
+    }
+
+    /// Dispose of the instances with the given disposer
+    pub(crate) fn with_disposer(self, disposer: Disposer) -> Self {
+        Self {
+            disposer: Some(disposer),
+            ..self
+        }
+    }
+}
+
+impl<'cx, S, P> Provider<'cx, &'static S> for WithLifetime<P>
+where
+    S: Service + ?Sized,
+    S::Output<'cx>: Send + Sync,
+    P: Provider<'cx, S>,
+{
+    fn provide(&'cx self, cx: &'cx Context, arg: S::Argument<'_>) -> &'cx S::Output<'cx> {
+        match self.try_provide(cx, arg) {
+            Ok(s) => s,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    fn try_provide(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'_>,
+    ) -> Result<&'cx S::Output<'cx>, Box<dyn Error + Send + Sync>> {
+        // SAFETY:
+        // - Binding ids are unique, so the instances with this id were all created by this
+        //   provider, and have the same type
+        // - The disposer was created for the output of this provider
+        unsafe {
+            cx.instances().get_or_try_init(self.id, self.disposer, || {
+                self.provider.try_provide(cx, arg)
+            })
+        }
+    }
+
+    #[inline(always)]
+    fn dependencies(&self) -> Vec<Dependency> {
+        self.provider.dependencies()
+    }
+
+    #[inline(always)]
+    fn is_singleton(&self) -> bool {
+        self.lifetime == Lifetime::Singleton
+    }
+}
+
+/// Disposes of instances of a type implementing [`Dispose`]
+#[derive(Clone, Copy)]
+pub(crate) struct Disposer {
+    /// The service the instances belong to
+    service: &'static str,
+
+    /// Pointer to the instance type's `dispose` implementation
+    //
+    // SAFETY:
+    // - Must only be called with a valid pointer to an instance of that type
+    dispose_fn: unsafe fn(*mut ()) -> Result<(), Box<dyn Error + Send + Sync>>,
+}
+
+impl Disposer {
+    /// Create a disposer for instances of type `T` of the given service
+    pub(crate) fn new<T: Dispose>(service: &'static str) -> Self {
+        unsafe fn dispose_value<T: Dispose>(
+            this: *mut (),
+        ) -> Result<(), Box<dyn Error + Send + Sync>> {
+            (*(this as *mut T)).dispose()
+        }
+
+        Self {
+            service,
+            dispose_fn: dispose_value::<T>,
+        }
+    }
+}
+
+/// The instances created in a context by providers with a [`Lifetime`]
+///
+/// Instances are disposed of and dropped in the reverse order they were created in, so that no
+/// instance outlives the instances it was created from.
+#[derive(Default)]
+pub(crate) struct Instances {
+    /// The instance of each binding, by binding id
+    ///
+    /// Cells are boxed, so that references to them stay valid while the map grows. They are only
+    /// removed when dropping.
+    cells: Mutex<HashMap<u64, Box<OnceCell<Instance>>>>,
+
+    /// The binding id of every instance, in the order it was created
+    created: Mutex<Vec<u64>>,
+}
+
+impl Instances {
+    /// Get the instance of a binding, creating it if it does not exist yet
+    ///
+    /// SAFETY:
+    /// - All instances with this id must have type `T`
+    /// - The disposer, if any, was created for the type `T`
+    pub(crate) unsafe fn get_or_try_init<T, E>(
+        &self,
+        id: u64,
+        disposer: Option<Disposer>,
+        init: impl FnOnce() -> Result<T, E>,
+    ) -> Result<&T, E>
+    where
+        T: Send + Sync,
+    {
+        let cell: *const OnceCell<Instance> = &**lock(&self.cells).entry(id).or_default();
+
+        // Note: The map must not be locked while the instance is being created, since creating it
+        // may create other instances.
+        //
+        // SAFETY:
+        // - The cell is boxed and only removed from the map when dropping, so it lives as long as
+        //   `self`
+        let instance = (*cell).get_or_try_init(|| {
+            let instance = Instance::new(init()?, disposer);
+            lock(&self.created).push(id);
+            Ok(instance)
+        })?;
+
+        Ok(instance.get::<T>())
+    }
+
+    /// Dispose of and drop every instance, in the reverse order they were created in
+    ///
+    /// Returns the errors of the instances that failed to be disposed of.
+    pub(crate) fn dispose(&mut self) -> Vec<(&'static str, Box<dyn Error + Send + Sync>)> {
+        let cells = self.cells.get_mut().unwrap_or_else(PoisonError::into_inner);
+        let created = self
+            .created
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        let mut errors = Vec::new();
+        while let Some(id) = created.pop() {
+            let instance = cells.remove(&id).and_then(|cell| cell.into_inner());
+            if let Some(Err(err)) = instance.map(|mut instance| instance.dispose()) {
+                errors.push(err);
+            }
+        }
+
+        errors
+    }
+}
+
+impl Drop for Instances {
+    fn drop(&mut self) {
+        // Note: There is no one to report errors to at this point
+        self.dispose();
+    }
+}
+
+/// Lock a mutex, ignoring poisoning
+///
+/// The mutexes in [`Instances`] are never held while running user code, so they can not be left in
+/// an inconsistent state.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A type-erased, owned instance
+struct Instance {
+    /// Type-erased pointer to the boxed value
+    this: NonNull<()>,
+
+    /// Pointer to the value's `drop` implementation
+    //
+    // SAFETY:
+    // - Must only be called with a valid `self.this` pointer
+    drop_fn: unsafe fn(*mut ()),
+
+    /// Disposes of the value before it is dropped
+    //
+    // SAFETY:
+    // - Must have been created for the type of the value
+    disposer: Option<Disposer>,
+}
+
+// SAFETY:
+// - Instances can only be created from values that are Send
+unsafe impl Send for Instance {}
+
+// SAFETY:
+// - Instances can only be created from values that are Sync
+unsafe impl Sync for Instance {}
+
+impl Instance {
+    /// Create an instance from a value
+    ///
+    /// SAFETY:
+    /// - The disposer, if any, was created for the type `T`
+    unsafe fn new<T: Send + Sync>(value: T, disposer: Option<Disposer>) -> Self {
+        unsafe fn drop_value<T>(this: *mut ()) {
+            std::mem::drop(Box::from_raw(this as *mut T));
+        }
+
+        // SAFETY:
+        // - A `Box`'s pointer is always guaranteed to be non-null
+        let this = NonNull::new_unchecked(Box::into_raw(Box::new(value)) as *mut _);
+
+        Self {
+            this,
+            drop_fn: drop_value::<T>,
+            disposer,
+        }
+    }
+
+    /// Dispose of the value, returning the service it belongs to along with the error on failure
+    fn dispose(&mut self) -> Result<(), (&'static str, Box<dyn Error + Send + Sync>)> {
+        match self.disposer {
+            // SAFETY:
+            // - The disposer was created for the type of the value
+            // - `Instance` owns the value, so `self.this` points to valid memory
+            Some(disposer) => unsafe { (disposer.dispose_fn)(self.this.as_ptr()) }
+                .map_err(|err| (disposer.service, err)),
+            None => Ok(()),
+        }
+    }
+
+    /// Get a reference to the value
+    ///
+    /// SAFETY:
+    /// - The instance was created from a value of type `T`
+    unsafe fn get<'a, T>(&self) -> &'a T {
+        &*(self.this.as_ptr() as *const T)
+    }
+}
+
+impl Drop for Instance {
+    fn drop(&mut self) {
+        // SAFETY:
+        // - `drop_fn` can only be called with `self.this`, which it is.
+        // - `Instance` owns the value, so `self.this` points to valid memory until now.
+        unsafe { (self.drop_fn)(self.this.as_ptr()) }
+    }
+}
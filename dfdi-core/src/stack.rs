@@ -0,0 +1,59 @@
+use std::cell::RefCell;
+
+use crate::{context::Key, ResolveError};
+
+/// A service being resolved, along with the key of its binding
+pub(crate) type Frame = (Key, &'static str);
+
+thread_local! {
+    /// The services being resolved on this thread, from the outermost one
+    static STACK: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Marks a service as being resolved on the current thread, until dropped
+pub(crate) struct StackGuard(());
+
+impl StackGuard {
+    /// Push a service onto the resolution stack, returning the service that was being resolved
+    /// before it, if any
+    ///
+    /// # Fails
+    /// If the key is already on the stack, in which case resolving the service would recurse
+    /// forever.
+    pub(crate) fn push(
+        key: Key,
+        service: &'static str,
+    ) -> Result<(Self, Option<Frame>), ResolveError> {
+        STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+
+            if let Some(start) = stack.iter().position(|(k, _)| *k == key) {
+                let cycle = stack[start..]
+                    .iter()
+                    .map(|(_, service)| *service)
+                    .chain([service])
+                    .collect();
+                return Err(ResolveError::Cycle(cycle));
+            }
+
+            let dependent = stack.last().copied();
+            stack.push((key, service));
+            Ok((Self(()), dependent))
+        })
+    }
+}
+
+impl Drop for StackGuard {
+    fn drop(&mut self) {
+        STACK.with(|stack| stack.borrow_mut().pop());
+    }
+}
+
+/// Wrap an error in the chain of services that are currently being resolved on this thread
+pub(crate) fn with_chain(err: ResolveError) -> ResolveError {
+    STACK.with(|stack| {
+        stack.borrow().iter().rev().fold(err, |err, (_, service)| {
+            ResolveError::Dependency(service, Box::new(err))
+        })
+    })
+}
@@ -1,4 +1,6 @@
-use crate::Context;
+use std::{any::type_name, error::Error, future::Future, pin::Pin};
+
+use crate::{context::Key, BindError, Context, ResolveError};
 
 /// A can construct a [`Service`] which references objects either inside itself or the provided
 /// [`Context`].
@@ -18,13 +20,52 @@ use crate::Context;
 /// // Print a random number
 /// println!("{}", cx.resolve::<Random>().0);
 /// ```
-pub trait Provider<'cx, S: Service>: Send + Sync + 'cx {
+pub trait Provider<'cx, S: Service + ?Sized>: Send + Sync + 'cx {
     /// Build the output object
-    // #! Remember to keep in sync with `ProvideFn`
     fn provide(&'cx self, cx: &'cx Context, arg: S::Argument<'_>) -> S::Output<'cx>;
+
+    /// Try to build the output object
+    ///
+    /// The [`Context`] uses this method to resolve services. Providers that can fail should
+    /// override it, and panic with the error in [`provide`](Self::provide). If the error is a
+    /// [`ResolveError`](crate::ResolveError), it is reported as a failed dependency of `S`.
+    ///
+    /// The default implementation never fails.
+    // #! Remember to keep in sync with `ProvideFn`
+    #[inline(always)]
+    fn try_provide(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'_>,
+    ) -> Result<S::Output<'cx>, Box<dyn Error + Send + Sync>> {
+        Ok(self.provide(cx, arg))
+    }
+
+    /// The services this provider resolves from the [`Context`]
+    ///
+    /// [`Context::validate`] uses this method to check that every dependency is bound, without
+    /// running the provider.
+    ///
+    /// The default implementation declares no dependencies.
+    #[inline(always)]
+    fn dependencies(&self) -> Vec<Dependency> {
+        Vec::new()
+    }
+
+    /// Whether the provider must always be run with the [`Context`] it is bound in, rather than
+    /// the one the service is resolved from
+    ///
+    /// Providers that keep their output for later resolutions must return `true`, since the
+    /// output could otherwise borrow from a sub-context that is dropped before it.
+    ///
+    /// The default implementation returns `false`.
+    #[inline(always)]
+    fn is_singleton(&self) -> bool {
+        false
+    }
 }
 
-/// A pointer to the underlying provider function.
+/// A pointer to the underlying provider's `try_provide` function.
 ///
 /// The first argument must be a pointer, otherwise miri's stacked borrows will reject this code.
 /// This is because casting a `*const ()` to, say, a `&'cx ()` resizes the alloc range that the
@@ -35,9 +76,93 @@ pub trait Provider<'cx, S: Service>: Send + Sync + 'cx {
 /// # SAFETY
 /// - The first argument must have the correct type
 /// - The first argument must live for 'cx
-// #! This __MUST__ be kept in sync with `Provider::provide` or bad things will happen
+// #! This __MUST__ be kept in sync with `Provider::try_provide` or bad things will happen
 pub(crate) type ProvideFn<'cx, S> =
-    unsafe fn(*const (), &'cx Context, <S as Service>::Argument<'_>) -> <S as Service>::Output<'cx>;
+    unsafe fn(
+        *const (),
+        &'cx Context,
+        <S as Service>::Argument<'_>,
+    ) -> Result<<S as Service>::Output<'cx>, Box<dyn Error + Send + Sync>>;
+
+/// An owned, type-erased future, as returned by an [`AsyncProvider`]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// An asynchronous version of [`Provider`], for services that need to await something before they
+/// can be constructed.
+///
+/// Any type implementing the appropriate [`Fn`] trait and returning a [`Future`] can be used as an
+/// async provider:
+/// ```
+/// # use dfdi::{Service, Context};
+/// #[derive(Service)]
+/// struct Config(String);
+///
+/// let mut cx = Context::new();
+/// cx.bind_async_fn::<Config, _>(|_cx, _arg| async { Config("config".to_string()) });
+///
+/// let config = pollster::block_on(cx.resolve_async::<Config>());
+/// assert_eq!(config.0, "config");
+/// ```
+pub trait AsyncProvider<'cx, S: Service + ?Sized>: Send + Sync + 'cx {
+    /// Build the output object
+    fn provide_async(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'cx>,
+    ) -> BoxFuture<'cx, S::Output<'cx>>;
+
+    /// Try to build the output object
+    ///
+    /// This is the asynchronous version of [`Provider::try_provide`].
+    ///
+    /// The default implementation never fails.
+    // #! Remember to keep in sync with `ProvideAsyncFn`
+    fn try_provide_async(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'cx>,
+    ) -> BoxFuture<'cx, Result<S::Output<'cx>, Box<dyn Error + Send + Sync>>> {
+        let future = self.provide_async(cx, arg);
+        Box::pin(async move { Ok(future.await) })
+    }
+
+    /// The services this provider resolves from the [`Context`]
+    ///
+    /// This is the asynchronous version of [`Provider::dependencies`].
+    ///
+    /// The default implementation declares no dependencies.
+    #[inline(always)]
+    fn dependencies(&self) -> Vec<Dependency> {
+        Vec::new()
+    }
+
+    /// Whether the provider must always be run with the [`Context`] it is bound in, rather than
+    /// the one the service is resolved from
+    ///
+    /// This is the asynchronous version of [`Provider::is_singleton`].
+    ///
+    /// The default implementation returns `false`.
+    #[inline(always)]
+    fn is_singleton(&self) -> bool {
+        false
+    }
+}
+
+/// A pointer to the underlying async provider's `try_provide_async` function.
+///
+/// See [`ProvideFn`] for the reason the first argument is a pointer.
+///
+/// # SAFETY
+/// - The first argument must have the correct type
+/// - The first argument must live for 'cx
+// #! This __MUST__ be kept in sync with `AsyncProvider::try_provide_async` or bad things will happen
+pub(crate) type ProvideAsyncFn<'cx, S> =
+    unsafe fn(
+        *const (),
+        &'cx Context,
+        <S as Service>::Argument<'cx>,
+    )
+        -> BoxFuture<'cx, Result<<S as Service>::Output<'cx>, Box<dyn Error + Send + Sync>>>;
 
 /// A key to an object that can be created by a [`Provider`] and stored in a [`Context`].
 ///
@@ -49,4 +174,156 @@ pub trait Service: 'static {
 
     /// An argument for the service provider
     type Argument<'arg>;
+
+    /// Resolve the service from a context, using the provider bound to it under the given name
+    ///
+    /// This is an implementation detail, which lets services such as `Option<S>` be resolved
+    /// through the binding of another service.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn resolve_in<'cx>(
+        cx: &'cx Context,
+        name: Option<&'static str>,
+        arg: Self::Argument<'_>,
+    ) -> Result<Self::Output<'cx>, ResolveError>
+    where
+        Self: Sized,
+    {
+        cx.resolve_key::<Self>(Key::of::<Self>(name), arg)
+    }
+
+    /// The dependency of a provider on the service bound under the given name
+    ///
+    /// This is an implementation detail, which lets services resolved through the binding of
+    /// another service depend on that service instead.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn dependency(name: Option<&'static str>) -> Dependency
+    where
+        Self: Sized,
+    {
+        Dependency {
+            key: Key::of::<Self>(name),
+            service: type_name::<Self>(),
+            optional: false,
+            deferred: false,
+        }
+    }
+}
+
+/// A trait object [`Service`] which values of type `T` can be converted into.
+///
+/// This allows binding a concrete type behind a trait object service with
+/// [`Context::bind_as`]. It is implemented for every type implementing the trait by the
+/// `#[service_trait]` attribute macro.
+pub trait Upcast<T>: Service {
+    /// Convert a value into the output of this service
+    fn upcast<'cx>(value: T) -> Self::Output<'cx>
+    where
+        T: 'cx;
+}
+
+/// A [`Service`] that can construct itself by resolving other services from a [`Context`].
+///
+/// This is usually implemented using the provided derive macro.
+pub trait Inject: Service {
+    /// Construct the service
+    fn inject<'cx>(
+        cx: &'cx Context,
+        arg: Self::Argument<'_>,
+    ) -> Result<Self::Output<'cx>, ResolveError>;
+
+    /// The services resolved while constructing the service
+    ///
+    /// The default implementation declares no dependencies.
+    #[inline(always)]
+    fn dependencies() -> Vec<Dependency> {
+        Vec::new()
+    }
+}
+
+/// A reusable group of bindings, installed with [`Context::install`]
+///
+/// Modules can install other modules they depend on while registering. Each module is only ever
+/// installed once in a context, so modules can be freely shared.
+pub trait Module: 'static {
+    /// Register the bindings of the module
+    fn register(self, cx: &mut Context) -> Result<(), BindError>;
+}
+
+/// An instance that needs to release its resources before it is dropped, such as a connection pool
+/// that must be flushed.
+///
+/// Instances of services bound with [`Context::bind_disposable`] are disposed of by the context
+/// that owns them.
+pub trait Dispose {
+    /// Release the resources held by this instance
+    fn dispose(&mut self) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// A service that a provider resolves from the [`Context`], as declared by
+/// [`Provider::dependencies`]
+#[derive(Clone, Copy, Debug)]
+pub struct Dependency {
+    pub(crate) key: Key,
+    service: &'static str,
+    optional: bool,
+    deferred: bool,
+}
+
+impl Dependency {
+    /// A dependency on the service `S`
+    #[inline(always)]
+    pub fn of<S: Service>() -> Self {
+        S::dependency(None)
+    }
+
+    /// A dependency on the service `S` bound under the given name
+    #[inline(always)]
+    pub fn named<S: Service>(name: &'static str) -> Self {
+        S::dependency(Some(name))
+    }
+
+    /// Mark the dependency as optional: The provider can do without it if it is not bound
+    #[inline(always)]
+    pub fn optional(self) -> Self {
+        Self {
+            optional: true,
+            ..self
+        }
+    }
+
+    /// Mark the dependency as deferred: The provider does not resolve it while it is running, so it
+    /// can not be part of a dependency cycle
+    #[inline(always)]
+    pub fn deferred(self) -> Self {
+        Self {
+            deferred: true,
+            ..self
+        }
+    }
+
+    /// The type name of the service
+    #[inline(always)]
+    pub fn service(&self) -> &'static str {
+        self.service
+    }
+
+    /// The name the service is bound under
+    #[inline(always)]
+    pub fn name(&self) -> Option<&'static str> {
+        self.key.name
+    }
+
+    /// Whether the dependency is optional
+    #[inline(always)]
+    pub fn is_optional(&self) -> bool {
+        self.optional
+    }
+
+    /// Whether the dependency is deferred
+    #[inline(always)]
+    pub fn is_deferred(&self) -> bool {
+        self.deferred
+    }
 }
@@ -0,0 +1,24 @@
+use proc_macro2::{Span, TokenStream};
+use proc_macro_crate::FoundCrate;
+use quote::quote;
+use syn::{Ident, Result};
+
+/// Find the path to the crate providing the dfdi traits: Either `dfdi` or `dfdi-core`
+pub fn crate_path() -> Result<TokenStream> {
+    let found = proc_macro_crate::crate_name("dfdi")
+        .or_else(|_| proc_macro_crate::crate_name("dfdi-core"))
+        .map_err(|_| {
+            syn::Error::new(
+                Span::call_site(),
+                "Crate `dfdi` or `dfdi-core` must be present in Cargo.toml",
+            )
+        })?;
+
+    Ok(match found {
+        FoundCrate::Itself => quote!(dfdi),
+        FoundCrate::Name(name) => {
+            let name = Ident::new(&name, Span::call_site());
+            quote!(#name)
+        }
+    })
+}
@@ -0,0 +1,226 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Data, DeriveInput, Expr, Field, Fields, GenericArgument, Ident, Lifetime, PathArguments,
+    Result, Token, Type,
+};
+
+use crate::{
+    crate_path::crate_path,
+    derive_service::{build_type_path, service_generics, ServiceTypeVisitor},
+};
+
+/// A single option in an #[inject(...)] attribute
+enum InjectOption {
+    /// `arg = <expr>`: Resolve the field with the given argument
+    Arg(Box<Expr>),
+    /// `default`: Use the default value of the field instead of resolving it
+    Default(Span),
+    /// `optional`: Resolve the inner type of an `Option` field, if the service is bound
+    Optional(Span),
+}
+
+impl Parse for InjectOption {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident = Ident::parse(input)?;
+        match &*ident.to_string() {
+            "arg" => {
+                input.parse::<Token![=]>()?;
+                Ok(Self::Arg(Box::new(Expr::parse(input)?)))
+            }
+            "default" => Ok(Self::Default(ident.span())),
+            "optional" => Ok(Self::Optional(ident.span())),
+            _ => Err(syn::Error::new(
+                ident.span(),
+                "Unknown inject option, expected one of `arg`, `default` or `optional`",
+            )),
+        }
+    }
+}
+
+/// The parsed #[inject(...)] attributes of a field
+#[derive(Default)]
+struct InjectAttr {
+    arg: Option<Expr>,
+    default: Option<Span>,
+    optional: Option<Span>,
+}
+
+impl InjectAttr {
+    fn from_field(field: &Field) -> Result<Self> {
+        let mut attr = Self::default();
+
+        for option in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("inject"))
+            .map(|attr| {
+                attr.parse_args_with(Punctuated::<InjectOption, Token![,]>::parse_terminated)
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+        {
+            let (span, duplicate) = match option {
+                InjectOption::Arg(expr) => (expr.span(), attr.arg.replace(*expr).is_some()),
+                InjectOption::Default(span) => (span, attr.default.replace(span).is_some()),
+                InjectOption::Optional(span) => (span, attr.optional.replace(span).is_some()),
+            };
+
+            if duplicate {
+                return Err(syn::Error::new(span, "Duplicate inject option"));
+            }
+        }
+
+        if let Some(span) = attr.default {
+            if attr.arg.is_some() || attr.optional.is_some() {
+                return Err(syn::Error::new(
+                    span,
+                    "`default` can not be combined with other inject options",
+                ));
+            }
+        }
+
+        Ok(attr)
+    }
+}
+
+pub fn derive_inject(input: DeriveInput) -> Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => {
+            return Err(syn::Error::new(
+                data.enum_token.span,
+                "Inject can only be derived for structs",
+            ))
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span,
+                "Inject can only be derived for structs",
+            ))
+        }
+    };
+
+    let krate = crate_path()?;
+
+    // Build the expression that resolves each field, and the dependency it declares
+    let (values, dependencies): (Vec<_>, Vec<_>) = fields
+        .iter()
+        .map(|field| field_value(&krate, field))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    let dependencies = dependencies.into_iter().flatten();
+
+    let ident = &input.ident;
+    let constructor = match fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote!(#ident { #(#names: #values),* })
+        }
+        Fields::Unnamed(_) => quote!(#ident(#(#values),*)),
+        Fields::Unit => quote!(#ident),
+    };
+
+    // The type Service is implemented on, with all lifetimes replaced by 'static
+    let mut service_ty = Type::Path(build_type_path(input.ident.clone(), &input.generics));
+    ServiceTypeVisitor::new(None, Lifetime::new("'static", Span::call_site()))
+        .visit(&mut service_ty)?;
+
+    let generics = service_generics(input.generics)?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    // Final impl
+    let expanded = quote! {
+        impl #impl_generics #krate::Inject for #service_ty #where_clause {
+            fn inject<'cx>(
+                cx: &'cx #krate::Context,
+                _arg: <Self as #krate::Service>::Argument<'_>,
+            ) -> ::std::result::Result<<Self as #krate::Service>::Output<'cx>, #krate::ResolveError> {
+                ::std::result::Result::Ok(#constructor)
+            }
+
+            fn dependencies() -> ::std::vec::Vec<#krate::Dependency> {
+                ::std::vec![#(#dependencies),*]
+            }
+        }
+    };
+
+    Ok(expanded)
+}
+
+/// Build the expression producing the value of a field, along with the dependency it resolves if
+/// any
+fn field_value(krate: &TokenStream, field: &Field) -> Result<(TokenStream, Option<TokenStream>)> {
+    let attr = InjectAttr::from_field(field)?;
+    let span = field.ty.span();
+
+    if attr.default.is_some() {
+        return Ok((
+            quote_spanned!(span=> ::std::default::Default::default()),
+            None,
+        ));
+    }
+
+    // Optional fields resolve the service inside the `Option`
+    let mut service_ty = match attr.optional {
+        Some(optional) => option_inner(&field.ty).cloned().ok_or_else(|| {
+            syn::Error::new(optional, "Optional fields must have an `Option<_>` type")
+        })?,
+        None => field.ty.clone(),
+    };
+
+    // The service type has all of its lifetimes replaced by 'static
+    ServiceTypeVisitor::new(None, Lifetime::new("'static", Span::call_site()))
+        .visit(&mut service_ty)?;
+
+    let resolve = match attr.arg {
+        Some(arg) => quote_spanned!(span=> cx.try_resolve_with::<#service_ty>(#arg)),
+        None => quote_spanned!(span=> cx.try_resolve::<#service_ty>()),
+    };
+
+    let dependency = match attr.optional {
+        Some(_) => quote_spanned!(span=> #krate::Dependency::of::<#service_ty>().optional()),
+        None => quote_spanned!(span=> #krate::Dependency::of::<#service_ty>()),
+    };
+
+    let value = match attr.optional {
+        Some(_) => quote_spanned! {span=>
+            match #resolve {
+                ::std::result::Result::Ok(service) => ::std::option::Option::Some(service),
+                ::std::result::Result::Err(#krate::ResolveError::ServiceUnbound(..)) => {
+                    ::std::option::Option::None
+                }
+                ::std::result::Result::Err(err) => return ::std::result::Result::Err(err),
+            }
+        },
+        None => quote_spanned!(span=> #resolve?),
+    };
+
+    Ok((value, Some(dependency)))
+}
+
+/// Get `T` out of an `Option<T>` type
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+
+    match args.args.first() {
+        Some(GenericArgument::Type(ty)) if args.args.len() == 1 => Some(ty),
+        _ => None,
+    }
+}
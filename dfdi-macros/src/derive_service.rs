@@ -1,89 +1,225 @@
 use proc_macro2::{Span, TokenStream};
-use proc_macro_crate::FoundCrate;
 use quote::quote;
 use syn::{
     parse::{Parse, ParseStream},
+    parse_quote_spanned,
     punctuated::{Pair, Punctuated},
     spanned::Spanned,
     token::Paren,
-    visit_mut::{visit_type_path_mut, VisitMut},
+    visit_mut::{visit_type_path_mut, visit_type_reference_mut, VisitMut},
     AngleBracketedGenericArguments, DeriveInput, Expr, ExprPath, GenericArgument, GenericParam,
-    Generics, Ident, Lifetime, Path, PathSegment, Result, Token, Type, TypePath, TypeTuple,
+    Generics, Ident, Lifetime, Path, PathSegment, PredicateLifetime, Result, Token, Type,
+    TypeParamBound, TypePath, TypeReference, TypeTuple, Visibility, WherePredicate,
 };
 
-/// Parsed #[service(Argument -> Output)] attribute
-struct ServiceAttr {
-    arg: Type,
-    out: Type,
+use crate::crate_path::crate_path;
+
+/// Parsed #[service(Argument -> Output, options...)] attribute
+#[derive(Default)]
+pub(crate) struct ServiceAttr {
+    arg: Option<Type>,
+    out: Option<Type>,
+    key: Option<Ident>,
+    factory: Option<Ident>,
+}
+
+impl ServiceAttr {
+    /// An attribute with the given signature and no options
+    pub(crate) fn with_signature(arg: Type, out: Type) -> Self {
+        Self {
+            arg: Some(arg),
+            out: Some(out),
+            ..Self::default()
+        }
+    }
 }
 
 impl Parse for ServiceAttr {
     fn parse(input: ParseStream) -> Result<Self> {
-        let arg = Type::parse(input)?;
-        input.parse::<Token![->]>()?;
-        let out = Type::parse(input)?;
+        let mut attr = Self::default();
+
+        // The signature is optional, and can only come first
+        if !(input.peek(Ident) && input.peek2(Token![=])) {
+            attr.arg = Some(Type::parse(input)?);
+            input.parse::<Token![->]>()?;
+            attr.out = Some(Type::parse(input)?);
 
-        Ok(Self { arg, out })
+            if input.is_empty() {
+                return Ok(attr);
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        for option in Punctuated::<ServiceOption, Token![,]>::parse_terminated(input)? {
+            let (span, duplicate) = match option {
+                ServiceOption::Arg(ty) => (ty.span(), attr.arg.replace(ty).is_some()),
+                ServiceOption::Output(ty) => (ty.span(), attr.out.replace(ty).is_some()),
+                ServiceOption::Key(ident) => (ident.span(), attr.key.replace(ident).is_some()),
+                ServiceOption::Factory(ident) => {
+                    (ident.span(), attr.factory.replace(ident).is_some())
+                }
+            };
+
+            if duplicate {
+                return Err(syn::Error::new(span, "Duplicate service option"));
+            }
+        }
+
+        Ok(attr)
+    }
+}
+
+/// A single option in a #[service(...)] attribute, after the signature
+enum ServiceOption {
+    /// `arg = <type>`: The argument type of the service
+    Arg(Type),
+    /// `output = <type>`: The output type of the service
+    Output(Type),
+    /// `key = <ident>`: Declare a marker type, and implement the service on it instead
+    Key(Ident),
+    /// `factory = <ident>`: Declare a type alias for the `Factory` of the service
+    Factory(Ident),
+}
+
+impl Parse for ServiceOption {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident = Ident::parse(input)?;
+        match &*ident.to_string() {
+            "arg" => {
+                input.parse::<Token![=]>()?;
+                Ok(Self::Arg(Type::parse(input)?))
+            }
+            "output" => {
+                input.parse::<Token![=]>()?;
+                Ok(Self::Output(Type::parse(input)?))
+            }
+            "key" => {
+                input.parse::<Token![=]>()?;
+                Ok(Self::Key(Ident::parse(input)?))
+            }
+            "factory" => {
+                input.parse::<Token![=]>()?;
+                Ok(Self::Factory(Ident::parse(input)?))
+            }
+            _ => Err(syn::Error::new(
+                ident.span(),
+                "Unknown service option, expected one of `arg`, `output`, `key` or `factory`",
+            )),
+        }
     }
 }
 
 pub fn derive_service(input: DeriveInput) -> Result<TokenStream> {
-    // Parse the #[service] attribute
+    // Parse the #[service] attributes: At most one for the type itself, and any number declaring
+    // marker services
     let mut service_attr = None;
+    let mut key_attrs = Vec::new();
     for attr in input
         .attrs
-        .into_iter()
+        .iter()
         .filter(|attr| attr.path.is_ident("service"))
     {
-        if service_attr.is_some() {
+        let parsed = attr.parse_args::<ServiceAttr>()?;
+        if parsed.key.is_some() {
+            key_attrs.push(parsed);
+        } else if service_attr.replace(parsed).is_some() {
             return Err(syn::Error::new(
                 attr.path.span(),
                 "Duplicate service attribute",
             ));
         }
+    }
+
+    let krate = crate_path()?;
+
+    // Build the TypePath refering to this type
+    let ty = build_type_path(input.ident.clone(), &input.generics);
+
+    // Replace all lifetimes on the original type with 'static to create the type Service will be
+    // implemented on.
+    let mut service_ty = Type::Path(ty.clone());
+    ServiceTypeVisitor::new(None, Lifetime::new("'static", Span::call_site()))
+        .visit(&mut service_ty)?;
+
+    let generics = service_generics(input.generics)?;
+
+    let mut expanded = service_impl(
+        &krate,
+        &input.vis,
+        &service_ty,
+        &generics,
+        Some(&ty),
+        service_attr.unwrap_or_default(),
+    )?;
+
+    // Marker services can not name the type parameters of the type
+    if let Some(param) = generics.params.iter().next() {
+        if let Some(key) = key_attrs.first().and_then(|attr| attr.key.as_ref()) {
+            let mut err = syn::Error::new(
+                key.span(),
+                "Marker services can not be declared on generic types",
+            );
+            err.combine(syn::Error::new(
+                param.span(),
+                "generic parameter declared here",
+            ));
+            return Err(err);
+        }
+    }
+
+    for mut attr in key_attrs {
+        let (vis, key) = (&input.vis, attr.key.take().unwrap());
+        let doc = format!("A marker service for `{}`", input.ident);
+        let key_ty = Type::Verbatim(quote!(#key));
+        let service = service_impl(&krate, vis, &key_ty, &generics, Some(&ty), attr)?;
+
+        expanded.extend(quote! {
+            #[doc = #doc]
+            #vis struct #key;
 
-        service_attr = Some(attr.parse_args::<ServiceAttr>()?);
+            #service
+        });
     }
 
-    // Find the path to the `Service` trait
-    let service_trait = match proc_macro_crate::crate_name("dfdi")
-        .or_else(|_| proc_macro_crate::crate_name("dfdi-core"))
-        .map_err(|_| {
-            syn::Error::new(
+    Ok(expanded)
+}
+
+/// Build the impl of `Service` on a type, along with the items requested by the attribute
+///
+/// `Self` in the output type refers to `self_ty`, or is forbidden if there is none.
+pub(crate) fn service_impl(
+    krate: &TokenStream,
+    vis: &Visibility,
+    service_ty: &Type,
+    generics: &Generics,
+    self_ty: Option<&TypePath>,
+    attr: ServiceAttr,
+) -> Result<TokenStream> {
+    // The types requested by the user for the argument and output, defaulting to an empty tuple
+    // and the `Self` type
+    let mut arg_ty = attr.arg.unwrap_or_else(|| {
+        Type::Tuple(TypeTuple {
+            paren_token: Paren {
+                span: Span::call_site(),
+            },
+            elems: Punctuated::new(),
+        })
+    });
+    let mut out_ty = match (attr.out, self_ty) {
+        (Some(out), _) => out,
+        (None, Some(self_ty)) => Type::Path(self_ty.clone()),
+        (None, None) => {
+            return Err(syn::Error::new(
                 Span::call_site(),
-                "Crate `dfdi` or `dfdi-core` must be present in Cargo.toml",
-            )
-        })? {
-        FoundCrate::Itself => quote!(dfdi::Service),
-        FoundCrate::Name(name) => {
-            let name = Ident::new(&name, Span::call_site());
-            quote!(#name::Service)
+                "The output type of the service must be specified",
+            ))
         }
     };
 
-    // Build the TypePath refering to this type
-    let ty = build_type_path(input.ident, &input.generics);
-
-    // The types requested by the user for the argument and output.
-    let (mut arg_ty, mut out_ty) = match service_attr {
-        Some(attr) => (attr.arg, attr.out),
-        None => (
-            // An empty tuple
-            Type::Tuple(TypeTuple {
-                paren_token: Paren {
-                    span: Span::call_site(),
-                },
-                elems: Punctuated::new(),
-            }),
-            // The `Self` type, as interpreted in the attribute.
-            Type::Path(ty.clone()),
-        ),
-    };
-
     // Patch the Output type:
     // - Replace all non-'static lifetimes with 'cx
     // - Replace all instances of the `Self` type with the default output type
-    ServiceTypeVisitor::new(Some(ty.clone()), Lifetime::new("'cx", Span::call_site()))
+    ServiceTypeVisitor::new(self_ty.cloned(), Lifetime::new("'cx", Span::call_site()))
         .visit(&mut out_ty)?;
 
     // Patch the Argument type:
@@ -91,34 +227,123 @@ pub fn derive_service(input: DeriveInput) -> Result<TokenStream> {
     // - Forbid using `Self` which makes little sense here
     ServiceTypeVisitor::new(None, Lifetime::new("'arg", Span::call_site())).visit(&mut arg_ty)?;
 
-    // Replace all lifetimes on the original type with 'static to create the type Service will be
-    // implemented on.
-    let mut service_ty = Type::Path(ty);
-    ServiceTypeVisitor::new(None, Lifetime::new("'static", Span::call_site()))
-        .visit(&mut service_ty)?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
 
-    // Remove the lifetimes from the type parameters, since they will not be generic
-    let mut ty_params = input.generics;
-    ty_params.params = ty_params
+    // The factory alias takes the same type parameters, without their bounds which type aliases
+    // do not enforce
+    let factory = attr.factory.map(|factory| {
+        let params = generics.params.iter().map(|param| match param {
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            }
+            GenericParam::Const(param) => {
+                let (ident, ty) = (&param.ident, &param.ty);
+                quote!(const #ident: #ty)
+            }
+            GenericParam::Lifetime(_) => unreachable!("lifetimes were removed"),
+        });
+
+        quote! {
+            #vis type #factory<'cx, #(#params),*> = #krate::Factory<'cx, #service_ty>;
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #krate::Service for #service_ty #where_clause {
+            type Output<'cx> = #out_ty;
+            type Argument<'arg> = #arg_ty;
+        }
+
+        #factory
+    })
+}
+
+/// Build the generics of an impl on the `'static` version of a type:
+/// - Remove the lifetime parameters, since they will not be generic
+/// - Replace the lifetime parameters with `'static` in outlives bounds, and forbid them in any
+///   other bound, since the output type of the service has a different lifetime
+/// - Require every type parameter to be `'static`, since services must be
+pub(crate) fn service_generics(mut generics: Generics) -> Result<Generics> {
+    let mut visitor = BoundsVisitor {
+        lifetimes: generics
+            .lifetimes()
+            .map(|def| def.lifetime.clone())
+            .collect(),
+        error: None,
+    };
+
+    generics.params = generics
         .params
         .into_pairs()
         .filter(|pair| !matches!(pair.value(), GenericParam::Lifetime(_)))
         .collect();
 
-    // Final impl
-    let expanded = quote! {
-        impl #ty_params #service_trait for #service_ty {
-            type Output<'cx> = #out_ty;
-            type Argument<'arg> = #arg_ty;
+    visitor.visit_generics_mut(&mut generics);
+    if let Some(err) = visitor.error {
+        return Err(err);
+    }
+
+    let params = generics.type_params().map(|param| param.ident.clone());
+    let bounds: Vec<WherePredicate> = params
+        .map(|param| parse_quote_spanned!(param.span()=> #param: 'static))
+        .collect();
+    generics.make_where_clause().predicates.extend(bounds);
+
+    Ok(generics)
+}
+
+/// Replace the lifetime parameters of a type in outlives bounds with `'static`, and produce an
+/// error for any other use of them
+struct BoundsVisitor {
+    lifetimes: Vec<Lifetime>,
+    error: Option<syn::Error>,
+}
+
+impl BoundsVisitor {
+    fn make_static(&self, lifetime: &mut Lifetime) {
+        if self.lifetimes.contains(lifetime) {
+            *lifetime = Lifetime::new("'static", lifetime.span());
         }
-    };
+    }
+}
 
-    Ok(expanded)
+impl VisitMut for BoundsVisitor {
+    fn visit_type_param_bound_mut(&mut self, i: &mut TypeParamBound) {
+        match i {
+            TypeParamBound::Lifetime(lifetime) => self.make_static(lifetime),
+            TypeParamBound::Trait(bound) => self.visit_trait_bound_mut(bound),
+        }
+    }
+
+    fn visit_predicate_lifetime_mut(&mut self, i: &mut PredicateLifetime) {
+        self.make_static(&mut i.lifetime);
+        for bound in &mut i.bounds {
+            self.make_static(bound);
+        }
+    }
+
+    fn visit_lifetime_mut(&mut self, i: &mut Lifetime) {
+        if self.lifetimes.contains(i) {
+            let err = syn::Error::new(
+                i.span(),
+                format!("The lifetime `{i}` can only be used in outlives bounds of services"),
+            );
+
+            self.error = Some(match self.error.take() {
+                Some(mut other) => {
+                    other.combine(err);
+                    other
+                }
+                None => err,
+            });
+        }
+    }
 }
 
 /// - Replace non-'static lifetimes with the provider lifetime
 /// - Replace `Self` with the supplied type, or produce an error if self_ty is None
-struct ServiceTypeVisitor {
+pub(crate) struct ServiceTypeVisitor {
     lifetime: Lifetime,
     self_ty: Option<TypePath>,
 
@@ -126,7 +351,7 @@ struct ServiceTypeVisitor {
 }
 
 impl ServiceTypeVisitor {
-    fn new(self_ty: Option<TypePath>, lifetime: Lifetime) -> Self {
+    pub(crate) fn new(self_ty: Option<TypePath>, lifetime: Lifetime) -> Self {
         Self {
             self_ty,
             lifetime,
@@ -134,7 +359,7 @@ impl ServiceTypeVisitor {
         }
     }
 
-    fn visit(&mut self, ty: &mut Type) -> Result<()> {
+    pub(crate) fn visit(&mut self, ty: &mut Type) -> Result<()> {
         self.visit_type_mut(ty);
         match self.error.take() {
             Some(err) => Err(err),
@@ -166,6 +391,15 @@ impl VisitMut for ServiceTypeVisitor {
         visit_type_path_mut(self, i);
     }
 
+    fn visit_type_reference_mut(&mut self, i: &mut TypeReference) {
+        // Elided lifetimes are also replaced
+        if i.lifetime.is_none() {
+            i.lifetime = Some(self.lifetime.clone());
+        }
+
+        visit_type_reference_mut(self, i);
+    }
+
     fn visit_lifetime_mut(&mut self, i: &mut Lifetime) {
         if i.ident != "'static" {
             *i = self.lifetime.clone();
@@ -173,11 +407,10 @@ impl VisitMut for ServiceTypeVisitor {
     }
 }
 
-fn build_type_path(ident: Ident, generics: &Generics) -> TypePath {
+pub(crate) fn build_type_path(ident: Ident, generics: &Generics) -> TypePath {
     let args = generics
         .params
         .pairs()
-        .into_iter()
         .map(|p| {
             let (val, sep) = p.into_tuple();
 
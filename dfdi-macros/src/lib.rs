@@ -1,9 +1,14 @@
 #![forbid(unsafe_code)]
 
+mod crate_path;
+mod derive_inject;
 mod derive_service;
+mod service_macro;
+mod service_trait;
 
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, DeriveInput, Error};
+use service_macro::MarkerServices;
+use syn::{parse_macro_input, spanned::Spanned, DeriveInput, Error, ItemTrait};
 
 /// Create an implementation of [`Service`] on a `'static` version of the original type.
 ///
@@ -15,6 +20,10 @@ use syn::{parse_macro_input, DeriveInput, Error};
 /// - Set `Output<'cx>` to the output type with all non-'static lifetimes replaced by `'cx`
 /// - Set `Argument<'arg>` to the argument type with all non-'static lifetimes replaced by `'arg`
 ///
+/// The derive works on structs, enums and unions. The bounds and where clause of the type are kept
+/// on the impl, and every type parameter is required to be `'static`. The lifetimes of the type can
+/// only appear in outlives bounds, such as `T: 'a`.
+///
 /// ```
 /// # use dfdi::Service;
 /// #[derive(Service)]
@@ -36,6 +45,64 @@ use syn::{parse_macro_input, DeriveInput, Error};
 /// //    type Argument<'arg> = bool;
 /// // }
 /// ```
+///
+/// The following named options can follow the signature, or replace it:
+/// - `arg = <Type>`: The argument type, same as in the signature
+/// - `output = <Type>`: The output type, same as in the signature
+/// - `key = <Name>`: Declare a zero-sized marker type with the visibility of the type, and
+///   implement `Service` on it, instead of on the type. `Self` in the output still refers to the
+///   type. Any number of these attributes can be used, along with at most one without a key.
+///   Marker services can not be declared on generic types.
+/// - `factory = <Name>`: Declare a type alias for the `Factory` of the service
+///
+/// ```
+/// # use dfdi::{Context, Service};
+/// #[derive(Service)]
+/// #[service(key = PrimaryDb, output = &Self)]
+/// #[service(key = ReplicaDb, arg = usize, output = &Self)]
+/// struct Pool(&'static str);
+///
+/// // The above generates:
+/// // impl Service for Pool { ... }
+/// //
+/// // struct PrimaryDb;
+/// //
+/// // impl Service for PrimaryDb {
+/// //     type Output<'cx> = &'cx Pool;
+/// //     type Argument<'arg> = ();
+/// // }
+/// //
+/// // struct ReplicaDb;
+/// //
+/// // impl Service for ReplicaDb {
+/// //     type Output<'cx> = &'cx Pool;
+/// //     type Argument<'arg> = usize;
+/// // }
+///
+/// let mut cx = Context::new();
+/// cx.bind_fn::<PrimaryDb>(|_cx, _arg| &Pool("db-1"));
+/// cx.bind_fn::<ReplicaDb>(|_cx, _replica| &Pool("db-2"));
+///
+/// assert_eq!(cx.resolve::<PrimaryDb>().0, "db-1");
+/// assert_eq!(cx.resolve_with::<ReplicaDb>(0).0, "db-2");
+/// ```
+///
+/// The factory alias has the same visibility and type parameters as the type:
+/// ```
+/// # use dfdi::{Context, Service};
+/// #[derive(Service)]
+/// #[service(u64 -> Self, factory = SessionFactory)]
+/// struct Session<'a>(&'a str, u64);
+///
+/// // The above also generates:
+/// // type SessionFactory<'cx> = Factory<'cx, Session<'static>>;
+///
+/// let mut cx = Context::new();
+/// cx.bind_fn::<Session>(|_cx, user| Session("admin", user));
+///
+/// let sessions: SessionFactory = cx.resolve::<SessionFactory>();
+/// assert_eq!(sessions.call(7).1, 7);
+/// ```
 #[proc_macro_derive(Service, attributes(service))]
 pub fn derive_service(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -43,3 +110,141 @@ pub fn derive_service(input: TokenStream) -> TokenStream {
         .unwrap_or_else(Error::into_compile_error)
         .into()
 }
+
+/// Implement `Inject` on a struct, by resolving each of its fields from the `Context`.
+///
+/// The struct must also implement [`Service`], with its default output type. The service type of
+/// each field is the type of the field with all lifetimes replaced by `'static`, same as with
+/// `#[derive(Service)]`. The following options can be used in an `#[inject(...)]` field attribute:
+/// - `arg = <expr>`: Resolve the field using the given service argument
+/// - `default`: Use the [`Default`] value of the field, instead of resolving it
+/// - `optional`: The field is an `Option`, which is `None` if its service is not bound
+///
+/// The `Injector` provider can then be used to bind the struct:
+/// ```
+/// # use dfdi::{Context, Inject, Injector, Service};
+/// #[derive(Service)]
+/// struct Database;
+///
+/// #[derive(Service)]
+/// #[service(u32 -> Self)]
+/// struct Page(u32);
+///
+/// #[derive(Service)]
+/// struct Tracer;
+///
+/// #[derive(Service, Inject)]
+/// struct Handler<'a> {
+///     db: &'a Database,
+///     #[inject(arg = 2)]
+///     page: Page,
+///     #[inject(optional)]
+///     tracer: Option<&'a Tracer>,
+///     #[inject(default)]
+///     requests: u64,
+/// }
+///
+/// let mut cx = Context::new();
+/// cx.bind_fn::<&Database>(|_cx, _arg| &Database);
+/// cx.bind_fn::<Page>(|_cx, page| Page(page));
+/// cx.bind::<Handler, Injector<Handler>>();
+///
+/// let handler = cx.resolve::<Handler>();
+/// assert_eq!(handler.page.0, 2);
+/// assert!(handler.tracer.is_none());
+/// ```
+#[proc_macro_derive(Inject, attributes(inject))]
+pub fn derive_inject(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive_inject::derive_inject(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Implement [`Service`] on the trait object type of a trait, so that it can be used as a service.
+///
+/// The output of the service is a boxed trait object. Any type implementing the trait that is also
+/// `Send + Sync` can be bound to the service using `Context::bind_as`. The trait can not have any
+/// generic parameters.
+///
+/// ```
+/// # use dfdi::{service_trait, Context};
+/// #[service_trait]
+/// trait Storage {
+///     fn get(&self, key: &str) -> Option<String>;
+/// }
+///
+/// // The above generates:
+/// // impl Service for dyn Storage {
+/// //     type Output<'cx> = Box<dyn Storage + Send + Sync + 'cx>;
+/// //     type Argument<'arg> = ();
+/// // }
+/// //
+/// // impl<T: Storage + Send + Sync> Upcast<T> for dyn Storage { ... }
+/// ```
+#[proc_macro_attribute]
+pub fn service_trait(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return Error::new(
+            proc_macro2::TokenStream::from(attr).span(),
+            "`service_trait` does not take any arguments",
+        )
+        .into_compile_error()
+        .into();
+    }
+
+    let input = parse_macro_input!(item as ItemTrait);
+    service_trait::service_trait(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Declare zero-sized marker types implementing [`Service`], to be used as service keys that are
+/// distinct from their output type.
+///
+/// Each service is declared as `Name: Argument -> Output`, where the argument defaults to `()`
+/// when omitted. Lifetimes in the types are handled the same as with `#[derive(Service)]`, and
+/// elided reference lifetimes are set to the lifetime of the context. Several services can be
+/// declared at once, separated by semicolons.
+///
+/// ```
+/// # use dfdi::{service, Context};
+/// pub struct Pool(&'static str);
+///
+/// service! {
+///     /// The primary database
+///     pub PrimaryDb -> &Pool;
+///     pub ReplicaDb: usize -> &Pool;
+/// }
+///
+/// // The above generates:
+/// // pub struct PrimaryDb;
+/// //
+/// // impl Service for PrimaryDb {
+/// //     type Output<'cx> = &'cx Pool;
+/// //     type Argument<'arg> = ();
+/// // }
+/// //
+/// // pub struct ReplicaDb;
+/// //
+/// // impl Service for ReplicaDb {
+/// //     type Output<'cx> = &'cx Pool;
+/// //     type Argument<'arg> = usize;
+/// // }
+///
+/// static POOLS: [Pool; 3] = [Pool("db-1"), Pool("db-2"), Pool("db-3")];
+///
+/// let mut cx = Context::new();
+/// cx.bind_fn::<PrimaryDb>(|_cx, _arg| &POOLS[0]);
+/// cx.bind_fn::<ReplicaDb>(|_cx, replica| &POOLS[1 + replica]);
+///
+/// assert_eq!(cx.resolve::<PrimaryDb>().0, "db-1");
+/// assert_eq!(cx.resolve_with::<ReplicaDb>(1).0, "db-3");
+/// ```
+#[proc_macro]
+pub fn service(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as MarkerServices);
+    service_macro::service(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,92 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    token::Paren,
+    Attribute, Generics, Ident, Result, Token, Type, TypeTuple, Visibility,
+};
+
+use crate::{
+    crate_path::crate_path,
+    derive_service::{service_impl, ServiceAttr},
+};
+
+/// The marker services declared in a `service!` invocation, separated by semicolons
+pub struct MarkerServices(Punctuated<MarkerService, Token![;]>);
+
+impl Parse for MarkerServices {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self(Punctuated::parse_terminated(input)?))
+    }
+}
+
+/// A single marker service: `<attrs> <vis> <ident>: <arg> -> <out>`, where the argument is optional
+struct MarkerService {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    ident: Ident,
+    arg: Type,
+    out: Type,
+}
+
+impl Parse for MarkerService {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = Attribute::parse_outer(input)?;
+        let vis = Visibility::parse(input)?;
+        let ident = Ident::parse(input)?;
+
+        let arg = if input.peek(Token![:]) {
+            input.parse::<Token![:]>()?;
+            Type::parse(input)?
+        } else {
+            // An empty tuple
+            Type::Tuple(TypeTuple {
+                paren_token: Paren {
+                    span: Span::call_site(),
+                },
+                elems: Punctuated::new(),
+            })
+        };
+
+        input.parse::<Token![->]>()?;
+        let out = Type::parse(input)?;
+
+        Ok(Self {
+            attrs,
+            vis,
+            ident,
+            arg,
+            out,
+        })
+    }
+}
+
+pub fn service(input: MarkerServices) -> Result<TokenStream> {
+    let krate = crate_path()?;
+
+    let mut expanded = TokenStream::new();
+    for service in input.0 {
+        let MarkerService {
+            attrs,
+            vis,
+            ident,
+            arg,
+            out,
+        } = service;
+
+        let service_ty = Type::Verbatim(quote!(#ident));
+        let attr = ServiceAttr::with_signature(arg, out);
+        let service_impl =
+            service_impl(&krate, &vis, &service_ty, &Generics::default(), None, attr)?;
+
+        expanded.extend(quote! {
+            #(#attrs)*
+            #vis struct #ident;
+
+            #service_impl
+        });
+    }
+
+    Ok(expanded)
+}
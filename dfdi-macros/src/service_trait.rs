@@ -0,0 +1,48 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, ItemTrait, Result};
+
+use crate::crate_path::crate_path;
+
+pub fn service_trait(input: ItemTrait) -> Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.params.span(),
+            "Generic traits can not be used as services",
+        ));
+    }
+
+    if let Some(where_clause) = &input.generics.where_clause {
+        return Err(syn::Error::new(
+            where_clause.span(),
+            "Traits with where clauses can not be used as services",
+        ));
+    }
+
+    let krate = crate_path()?;
+    let ident = &input.ident;
+
+    let expanded = quote! {
+        #input
+
+        impl #krate::Service for dyn #ident {
+            type Output<'cx> = ::std::boxed::Box<dyn #ident + ::std::marker::Send + ::std::marker::Sync + 'cx>;
+            type Argument<'arg> = ();
+        }
+
+        impl<T> #krate::Upcast<T> for dyn #ident
+        where
+            T: #ident + ::std::marker::Send + ::std::marker::Sync,
+        {
+            #[inline(always)]
+            fn upcast<'cx>(value: T) -> <Self as #krate::Service>::Output<'cx>
+            where
+                T: 'cx,
+            {
+                ::std::boxed::Box::new(value)
+            }
+        }
+    };
+
+    Ok(expanded)
+}
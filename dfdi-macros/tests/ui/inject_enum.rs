@@ -0,0 +1,9 @@
+use dfdi::{Inject, Service};
+
+#[derive(Service, Inject)]
+enum Backend {
+    Memory,
+    Disk,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use dfdi::{Context, Service};
+
+#[derive(Service)]
+enum Backend {
+    Memory,
+    Disk(&'static str),
+}
+
+fn main() {
+    let mut cx = Context::new();
+    cx.bind_fn::<Backend>(|_cx, _arg| Backend::Disk("/var/lib/app"));
+
+    assert!(matches!(cx.resolve::<Backend>(), Backend::Disk("/var/lib/app")));
+    assert!(!matches!(cx.resolve::<Backend>(), Backend::Memory));
+}
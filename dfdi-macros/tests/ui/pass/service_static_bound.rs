@@ -0,0 +1,11 @@
+use dfdi::{Context, Service};
+
+#[derive(Service)]
+struct Wrapper<T>(T);
+
+fn main() {
+    let mut cx = Context::new();
+    cx.bind_fn::<Wrapper<u32>>(|_cx, _arg| Wrapper(42));
+
+    assert_eq!(cx.resolve::<Wrapper<u32>>().0, 42);
+}
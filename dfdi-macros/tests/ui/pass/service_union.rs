@@ -0,0 +1,15 @@
+use dfdi::{Context, Service};
+
+#[derive(Service)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {
+    let mut cx = Context::new();
+    cx.bind_fn::<Bits>(|_cx, _arg| Bits { float: 1.0 });
+
+    let bits = cx.resolve::<Bits>();
+    assert_eq!(unsafe { bits.int }, 1.0f32.to_bits());
+}
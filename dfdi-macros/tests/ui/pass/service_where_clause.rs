@@ -0,0 +1,15 @@
+use std::fmt::Debug;
+
+use dfdi::{Context, Service};
+
+#[derive(Service)]
+struct Labeled<T: Clone>(T)
+where
+    T: Debug;
+
+fn main() {
+    let mut cx = Context::new();
+    cx.bind_fn::<Labeled<String>>(|_cx, _arg| Labeled("primary".to_string()));
+
+    assert_eq!(format!("{:?}", cx.resolve::<Labeled<String>>().0), "\"primary\"");
+}
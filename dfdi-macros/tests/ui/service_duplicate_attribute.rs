@@ -0,0 +1,8 @@
+use dfdi::Service;
+
+#[derive(Service)]
+#[service(u32 -> Self)]
+#[service(u64 -> Self)]
+struct Session(u64);
+
+fn main() {}
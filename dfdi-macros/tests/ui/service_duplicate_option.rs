@@ -0,0 +1,7 @@
+use dfdi::Service;
+
+#[derive(Service)]
+#[service(u64 -> Self, arg = u32)]
+struct Session(u64);
+
+fn main() {}
@@ -0,0 +1,7 @@
+use dfdi::Service;
+
+#[derive(Service)]
+#[service(key = PrimaryCache, output = &Self)]
+struct Cache<T>(T);
+
+fn main() {}
@@ -0,0 +1,8 @@
+use dfdi::Service;
+
+#[derive(Service)]
+struct Ref<'a, T>(&'a T)
+where
+    T: PartialEq<&'a str>;
+
+fn main() {}
@@ -0,0 +1,7 @@
+use dfdi::Service;
+
+#[derive(Service)]
+#[service(Self -> Self)]
+struct Config(u32);
+
+fn main() {}
@@ -0,0 +1,7 @@
+use dfdi::Service;
+
+#[derive(Service)]
+#[service(u64 -> Self, builder = SessionBuilder)]
+struct Session(u64);
+
+fn main() {}
@@ -0,0 +1,113 @@
+use std::{error::Error, future::Future};
+
+use async_lock::OnceCell;
+
+use dfdi_core::{AsyncProvider, BoxFuture, Context, Dependency, Service};
+
+/// Async cached provider
+///
+/// The asynchronous version of [`Cached`](crate::Cached). Concurrent resolutions wait for a single
+/// call to the underlying provider, and share its result. It does not depend on any particular
+/// async runtime.
+///
+/// ```
+/// # use dfdi::{AsyncCached, Context, Service};
+/// #[derive(Service)]
+/// struct Connection(u64);
+///
+/// let mut cx = Context::new();
+/// cx.bind_async_with::<&Connection>(AsyncCached::new_fn(|_cx, _arg| async {
+///     Connection(rand::random())
+/// }));
+///
+/// pollster::block_on(async {
+///     let first = cx.resolve_async::<&Connection>().await;
+///     let second = cx.resolve_async::<&Connection>().await;
+///     assert_eq!(first.0, second.0);
+/// });
+/// ```
+pub struct AsyncCached<'cx, S, P>
+where
+    S: Service + ?Sized,
+    P: AsyncProvider<'cx, S>,
+{
+    provider: P,
+    cache: OnceCell<S::Output<'cx>>,
+}
+
+impl<'cx, S, P> AsyncCached<'cx, S, P>
+where
+    S: Service + ?Sized,
+    P: AsyncProvider<'cx, S>,
+{
+    /// Create a new async cached provider
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider,
+            cache: OnceCell::new(),
+        }
+    }
+}
+
+impl<'cx, S, F, Fut> AsyncCached<'cx, S, F>
+where
+    S: Service + ?Sized,
+    F: Fn(&'cx Context, S::Argument<'cx>) -> Fut + Send + Sync + 'cx,
+    Fut: Future<Output = S::Output<'cx>> + Send + 'cx,
+{
+    /// Equivelant to calling [`AsyncCached::new`] with an async function as the provider
+    #[inline(always)]
+    pub fn new_fn(provider: F) -> Self {
+        Self::new(provider)
+    }
+}
+
+impl<'cx, S, P> AsyncProvider<'cx, &'static S> for AsyncCached<'cx, S, P>
+where
+    S: Service + ?Sized,
+    S::Output<'cx>: Send + Sync,
+    S::Argument<'cx>: Send,
+    P: AsyncProvider<'cx, S>,
+{
+    fn provide_async(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'cx>,
+    ) -> BoxFuture<'cx, &'cx S::Output<'cx>> {
+        Box::pin(
+            self.cache
+                .get_or_init(move || self.provider.provide_async(cx, arg)),
+        )
+    }
+
+    fn try_provide_async(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'cx>,
+    ) -> BoxFuture<'cx, Result<&'cx S::Output<'cx>, Box<dyn Error + Send + Sync>>> {
+        Box::pin(
+            self.cache
+                .get_or_try_init(move || self.provider.try_provide_async(cx, arg)),
+        )
+    }
+
+    #[inline(always)]
+    fn dependencies(&self) -> Vec<Dependency> {
+        self.provider.dependencies()
+    }
+    #[inline(always)]
+    fn is_singleton(&self) -> bool {
+        true
+    }
+}
+
+impl<'cx, S, P> Default for AsyncCached<'cx, S, P>
+where
+    S: Service + ?Sized,
+    P: AsyncProvider<'cx, S> + Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new(P::default())
+    }
+}
@@ -1,6 +1,8 @@
+use std::error::Error;
+
 use once_cell::sync::OnceCell;
 
-use dfdi_core::{Context, Provider, Service};
+use dfdi_core::{Context, Dependency, Provider, Service};
 
 /// Cached provider
 ///
@@ -8,7 +10,7 @@ use dfdi_core::{Context, Provider, Service};
 /// on all calls
 pub struct Cached<'cx, S, P>
 where
-    S: Service,
+    S: Service + ?Sized,
     P: Provider<'cx, S>,
 {
     provider: P,
@@ -17,7 +19,7 @@ where
 
 impl<'cx, S, P> Cached<'cx, S, P>
 where
-    S: Service,
+    S: Service + ?Sized,
     P: Provider<'cx, S>,
 {
     /// Create a new cached provider
@@ -31,7 +33,7 @@ where
 
 impl<'cx, S, F> Cached<'cx, S, F>
 where
-    S: Service,
+    S: Service + ?Sized,
     F: Fn(&'cx Context, S::Argument<'_>) -> S::Output<'cx> + Send + Sync + 'cx,
 {
     /// Equivelant to calling [`Cached::new`] with a provider wrapped in a
@@ -44,18 +46,36 @@ where
 
 impl<'cx, S, P> Provider<'cx, &'static S> for Cached<'cx, S, P>
 where
-    S: Service,
+    S: Service + ?Sized,
     S::Output<'cx>: Send + Sync,
     P: Provider<'cx, S>,
 {
     fn provide(&'cx self, cx: &'cx Context, arg: S::Argument<'_>) -> &'cx S::Output<'cx> {
         self.cache.get_or_init(|| self.provider.provide(cx, arg))
     }
+
+    fn try_provide(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'_>,
+    ) -> Result<&'cx S::Output<'cx>, Box<dyn Error + Send + Sync>> {
+        self.cache
+            .get_or_try_init(|| self.provider.try_provide(cx, arg))
+    }
+
+    #[inline(always)]
+    fn dependencies(&self) -> Vec<Dependency> {
+        self.provider.dependencies()
+    }
+    #[inline(always)]
+    fn is_singleton(&self) -> bool {
+        true
+    }
 }
 
 impl<'cx, S, P> Default for Cached<'cx, S, P>
 where
-    S: Service,
+    S: Service + ?Sized,
     P: Provider<'cx, S> + Default,
 {
     #[inline]
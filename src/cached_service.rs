@@ -3,7 +3,7 @@ use dfdi_core::{Context, Provider, Service};
 /// Cached service
 ///
 /// A provider that returns the same reference on every call
-pub struct CachedService<'cx, S: Service>(pub S::Output<'cx>);
+pub struct CachedService<'cx, S: Service + ?Sized>(pub S::Output<'cx>);
 
 impl<'cx, S: Service> CachedService<'cx, S> {
     /// Create a new cached service
@@ -15,7 +15,7 @@ impl<'cx, S: Service> CachedService<'cx, S> {
 
 impl<'cx, S> Provider<'cx, &'static S> for CachedService<'cx, S>
 where
-    S: Service,
+    S: Service + ?Sized,
     S::Output<'cx>: Send + Sync,
 {
     #[inline(always)]
@@ -0,0 +1,75 @@
+use std::{any::type_name, error::Error, marker::PhantomData};
+
+use dfdi_core::{Context, Provider, Service};
+
+/// Fallible provider
+///
+/// A provider that wraps a function returning a `Result`. Errors are reported by
+/// [`Context::try_resolve`] instead of being turned into a panic right away. A
+/// [`ResolveError`](crate::ResolveError) returned by the function is reported as a failed
+/// dependency.
+///
+/// ```
+/// # use dfdi::{Context, Fallible, Service};
+/// #[derive(Service)]
+/// struct Token(String);
+///
+/// #[derive(Service)]
+/// struct User(String);
+///
+/// let mut cx = Context::new();
+/// cx.bind_with::<User>(Fallible::new(|cx, _arg| {
+///     let token = cx.try_resolve::<Token>()?;
+///     Ok::<_, dfdi::ResolveError>(User(token.0))
+/// }));
+///
+/// let err = cx.try_resolve::<User>().err().unwrap();
+/// assert_eq!(err.chain(), [std::any::type_name::<User>(), std::any::type_name::<Token>()]);
+/// ```
+pub struct Fallible<'cx, S: Service, F> {
+    func: F,
+    _phantom: PhantomData<fn(&'cx ()) -> S>,
+}
+
+impl<'cx, S: Service, F> Fallible<'cx, S, F> {
+    /// Create a new fallible provider
+    #[inline(always)]
+    pub fn new<E>(func: F) -> Self
+    where
+        F: Fn(&'cx Context, S::Argument<'_>) -> Result<S::Output<'cx>, E> + Send + Sync + 'cx,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        Self {
+            func,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'cx, S, F, E> Provider<'cx, S> for Fallible<'cx, S, F>
+where
+    S: Service,
+    F: Fn(&'cx Context, S::Argument<'_>) -> Result<S::Output<'cx>, E> + Send + Sync + 'cx,
+    E: Into<Box<dyn Error + Send + Sync>>,
+{
+    #[track_caller]
+    fn provide(&'cx self, cx: &'cx Context, arg: S::Argument<'_>) -> S::Output<'cx> {
+        match self.try_provide(cx, arg) {
+            Ok(s) => s,
+            Err(err) => panic!(
+                "provider for service `{}` failed: {}",
+                type_name::<S>(),
+                err
+            ),
+        }
+    }
+
+    #[inline(always)]
+    fn try_provide(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'_>,
+    ) -> Result<S::Output<'cx>, Box<dyn Error + Send + Sync>> {
+        (self.func)(cx, arg).map_err(Into::into)
+    }
+}
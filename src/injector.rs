@@ -0,0 +1,48 @@
+use std::{error::Error, marker::PhantomData};
+
+use dfdi_core::{Context, Dependency, Inject, Provider};
+
+/// Injecting provider
+///
+/// A provider that constructs a service implementing [`Inject`], usually by resolving each of its
+/// fields from the context.
+pub struct Injector<S: Inject>(PhantomData<fn() -> S>);
+
+impl<S: Inject> Injector<S> {
+    /// Create a new injecting provider
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<'cx, S: Inject> Provider<'cx, S> for Injector<S> {
+    #[track_caller]
+    fn provide(&'cx self, cx: &'cx Context, arg: S::Argument<'_>) -> S::Output<'cx> {
+        match S::inject(cx, arg) {
+            Ok(s) => s,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    #[inline(always)]
+    fn try_provide(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'_>,
+    ) -> Result<S::Output<'cx>, Box<dyn Error + Send + Sync>> {
+        Ok(S::inject(cx, arg)?)
+    }
+
+    #[inline(always)]
+    fn dependencies(&self) -> Vec<Dependency> {
+        S::dependencies()
+    }
+}
+
+impl<S: Inject> Default for Injector<S> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,119 @@
+use once_cell::sync::OnceCell;
+
+use dfdi_core::{Context, Dependency, ResolveError, Service};
+
+/// Lazy service
+///
+/// Resolving `Lazy<S>` does not run the provider of `S`. It returns a handle which resolves `S`
+/// from the same context the first time it is dereferenced, and keeps the result for later uses.
+/// This avoids building services that are only used on rare code paths, and lets two services
+/// refer to each other without forming a dependency cycle while they are being created.
+///
+/// ```
+/// # use dfdi::{Context, Lazy, Service};
+/// #[derive(Service)]
+/// struct Report(&'static str);
+///
+/// let mut cx = Context::new();
+/// cx.bind_fn::<Report>(|_cx, _arg| Report("quarterly"));
+///
+/// let report = cx.resolve::<Lazy<Report>>();
+/// assert_eq!(report.0, "quarterly");
+/// ```
+///
+/// Like other service types, `S` has all of its lifetimes set to `'static`:
+/// ```
+/// # use dfdi::{Cached, Context, Inject, Injector, Lazy, Service};
+/// #[derive(Service, Inject)]
+/// struct Parent<'a> {
+///     child: Lazy<'a, &'static Child<'static>>,
+/// }
+///
+/// #[derive(Service, Inject)]
+/// struct Child<'a> {
+///     parent: &'a Parent<'a>,
+/// }
+///
+/// let mut cx = Context::new();
+/// cx.bind_with::<&Parent>(Cached::new(Injector::<Parent>::default()));
+/// cx.bind_with::<&Child>(Cached::new(Injector::<Child>::default()));
+///
+/// let parent = cx.resolve::<&Parent>();
+/// assert!(std::ptr::eq(parent.child.parent, parent));
+/// ```
+///
+/// The service is resolved using its default argument. If it is bound under a name, the handle
+/// must be resolved under that same name.
+pub struct Lazy<'cx, S: Service> {
+    cx: &'cx Context<'cx>,
+    name: Option<&'static str>,
+    cell: OnceCell<S::Output<'cx>>,
+}
+
+impl<'cx, S> Lazy<'cx, S>
+where
+    S: Service,
+    S::Argument<'static>: Default,
+{
+    /// Get the service, resolving it if this is the first use of the handle
+    ///
+    /// # Panics
+    /// If the service resolution fails. See [`try_get`](Self::try_get) for a fallible version of
+    /// this function.
+    pub fn get(&self) -> &S::Output<'cx> {
+        self.cell.get_or_init(|| match self.name {
+            Some(name) => self.cx.resolve_named::<S>(name),
+            None => self.cx.resolve::<S>(),
+        })
+    }
+
+    /// Try to get the service, resolving it if this is the first use of the handle
+    ///
+    /// # Fails
+    /// This function will fail if the service resolution fails. A later call resolves the service
+    /// again.
+    ///
+    /// See [`get`](Self::get) for the panicking version of this function.
+    pub fn try_get(&self) -> Result<&S::Output<'cx>, ResolveError> {
+        self.cell.get_or_try_init(|| match self.name {
+            Some(name) => self.cx.try_resolve_named::<S>(name),
+            None => self.cx.try_resolve::<S>(),
+        })
+    }
+}
+
+impl<'cx, S> std::ops::Deref for Lazy<'cx, S>
+where
+    S: Service,
+    S::Argument<'static>: Default,
+{
+    type Target = S::Output<'cx>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+impl<S: Service> Service for Lazy<'static, S> {
+    type Output<'cx> = Lazy<'cx, S>;
+    type Argument<'arg> = ();
+
+    #[inline(always)]
+    fn resolve_in<'cx>(
+        cx: &'cx Context,
+        name: Option<&'static str>,
+        _arg: (),
+    ) -> Result<Lazy<'cx, S>, ResolveError> {
+        Ok(Lazy {
+            cx,
+            name,
+            cell: OnceCell::new(),
+        })
+    }
+
+    #[inline(always)]
+    fn dependency(name: Option<&'static str>) -> Dependency {
+        S::dependency(name).deferred()
+    }
+}
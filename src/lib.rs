@@ -1,15 +1,36 @@
 #![forbid(unsafe_code)]
 
-pub use dfdi_core::{BindError, Context, Provider, Service, UnbindError};
+pub use dfdi_core::{
+    AsyncProvider, BindError, BoxFuture, Contains, Context, ContextBuilder, Dependency,
+    DependencyGraph, Dispose, Factory, HCons, HList, HNil, Here, Inject, Lifetime, Module,
+    Provider, ResolveError, Service, ServiceDescriptor, ServiceNode, ShutdownError, There,
+    UnbindError, Upcast, ValidationError,
+};
 
 #[cfg(feature = "derive")]
-pub use dfdi_macros::Service;
+pub use dfdi_macros::{service, service_trait, Inject, Service};
 
+mod async_cached;
 mod cached;
 mod cached_service;
+mod fallible;
+mod injector;
+mod lazy;
+mod resettable;
+mod shared;
+mod shared_service;
+mod thread_local_cached;
 
+pub use async_cached::AsyncCached;
 pub use cached::Cached;
 pub use cached_service::CachedService;
+pub use fallible::Fallible;
+pub use injector::Injector;
+pub use lazy::Lazy;
+pub use resettable::{ResetHandle, Resettable};
+pub use shared::Shared;
+pub use shared_service::SharedService;
+pub use thread_local_cached::ThreadLocalCached;
 
 /// Type hint to the rust compiler to treat appropriately typed closures as providers.
 ///
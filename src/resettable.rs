@@ -0,0 +1,170 @@
+use std::{
+    error::Error,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, PoisonError, RwLock,
+    },
+};
+
+use dfdi_core::{Context, Dependency, Provider, Service};
+
+/// Resettable cached provider
+///
+/// A provider that works like [`Shared`](crate::Shared), except that its value can be reset
+/// through a [`ResetHandle`]. The next resolution after a reset calls the underlying provider
+/// again.
+///
+/// Values are handed out through shared pointers, so values resolved before a reset stay valid,
+/// and are freed once the last pointer to them is dropped.
+///
+/// ```
+/// # use dfdi::{Context, Resettable, Service};
+/// # use std::sync::{atomic::{AtomicU32, Ordering}, Arc};
+/// #[derive(Service)]
+/// struct Config(u32);
+///
+/// static VERSION: AtomicU32 = AtomicU32::new(1);
+///
+/// let cache = Resettable::new_fn(|_cx, _arg| Config(VERSION.load(Ordering::SeqCst)));
+/// let reset = cache.handle();
+///
+/// let mut cx = Context::new();
+/// cx.bind_with::<Arc<Config>>(cache);
+///
+/// let old = cx.resolve::<Arc<Config>>();
+/// VERSION.store(2, Ordering::SeqCst);
+/// reset.reset();
+///
+/// assert_eq!(cx.resolve::<Arc<Config>>().0, 2);
+/// assert_eq!(old.0, 1);
+///
+/// // The old value is freed once it is no longer used
+/// let weak = Arc::downgrade(&old);
+/// drop(old);
+/// assert!(weak.upgrade().is_none());
+/// ```
+pub struct Resettable<'cx, S, P>
+where
+    S: Service + ?Sized,
+    P: Provider<'cx, S>,
+{
+    provider: P,
+
+    /// The current value, along with the epoch it was created in
+    current: RwLock<Option<(usize, Arc<S::Output<'cx>>)>>,
+
+    /// The number of resets so far, shared with the reset handles
+    epoch: Arc<AtomicUsize>,
+}
+
+/// A handle that resets the value of a [`Resettable`] provider
+#[derive(Clone)]
+pub struct ResetHandle(Arc<AtomicUsize>);
+
+impl ResetHandle {
+    /// Reset the value of the provider, so that the next resolution creates a new one
+    pub fn reset(&self) {
+        self.0.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+impl<'cx, S, P> Resettable<'cx, S, P>
+where
+    S: Service + ?Sized,
+    P: Provider<'cx, S>,
+{
+    /// Create a new resettable cached provider
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider,
+            current: RwLock::new(None),
+            epoch: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Create a handle that resets the value of this provider
+    pub fn handle(&self) -> ResetHandle {
+        ResetHandle(self.epoch.clone())
+    }
+
+    /// Get the value of the current epoch, creating it if it does not exist yet
+    fn get_or_try_init<E>(
+        &self,
+        init: impl FnOnce() -> Result<S::Output<'cx>, E>,
+    ) -> Result<Arc<S::Output<'cx>>, E> {
+        let epoch = self.epoch.load(Ordering::Acquire);
+        let fresh = |current: &Option<(usize, Arc<S::Output<'cx>>)>| match current {
+            Some((created, value)) if *created >= epoch => Some(value.clone()),
+            _ => None,
+        };
+
+        if let Some(value) = fresh(&self.current.read().unwrap_or_else(PoisonError::into_inner)) {
+            return Ok(value);
+        }
+
+        // Note: Another thread may have created the value while the lock was released
+        let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+        if let Some(value) = fresh(&current) {
+            return Ok(value);
+        }
+
+        let value = Arc::new(init()?);
+        *current = Some((epoch, value.clone()));
+        Ok(value)
+    }
+}
+
+impl<'cx, S, F> Resettable<'cx, S, F>
+where
+    S: Service + ?Sized,
+    F: Fn(&'cx Context, S::Argument<'_>) -> S::Output<'cx> + Send + Sync + 'cx,
+{
+    /// Equivelant to calling [`Resettable::new`] with a provider wrapped in a
+    /// [`provider_fn`](crate::provider_fn) type hint
+    #[inline(always)]
+    pub fn new_fn(provider: F) -> Self {
+        Self::new(provider)
+    }
+}
+
+impl<'cx, S, P> Provider<'cx, Arc<S>> for Resettable<'cx, S, P>
+where
+    S: Service + ?Sized,
+    S::Output<'cx>: Send + Sync,
+    P: Provider<'cx, S>,
+{
+    fn provide(&'cx self, cx: &'cx Context, arg: S::Argument<'_>) -> Arc<S::Output<'cx>> {
+        match self.try_provide(cx, arg) {
+            Ok(value) => value,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    fn try_provide(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'_>,
+    ) -> Result<Arc<S::Output<'cx>>, Box<dyn Error + Send + Sync>> {
+        self.get_or_try_init(|| self.provider.try_provide(cx, arg))
+    }
+
+    #[inline(always)]
+    fn dependencies(&self) -> Vec<Dependency> {
+        self.provider.dependencies()
+    }
+    #[inline(always)]
+    fn is_singleton(&self) -> bool {
+        true
+    }
+}
+
+impl<'cx, S, P> Default for Resettable<'cx, S, P>
+where
+    S: Service + ?Sized,
+    P: Provider<'cx, S> + Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new(P::default())
+    }
+}
@@ -0,0 +1,131 @@
+use std::{error::Error, sync::Arc};
+
+use once_cell::sync::OnceCell;
+
+use dfdi_core::{Context, Dependency, Provider, Service};
+
+/// Shared provider
+///
+/// A provider that calls the underlying provider on the first call and returns a shared pointer to
+/// the result of that on all calls. Unlike [`Cached`](crate::Cached), the shared pointer does not
+/// borrow from the context, so services that do not borrow anything can be moved into threads or
+/// tasks that outlive it.
+///
+/// ```
+/// # use dfdi::{Context, Service, Shared};
+/// # use std::sync::Arc;
+/// #[derive(Service)]
+/// struct Database(&'static str);
+///
+/// let mut cx = Context::new();
+/// cx.bind_with::<Arc<Database>>(Shared::new_fn(|_cx, _arg| Database("postgres")));
+///
+/// let db: Arc<Database> = cx.resolve::<Arc<Database>>();
+/// drop(cx);
+///
+/// std::thread::spawn(move || assert_eq!(db.0, "postgres")).join().unwrap();
+/// ```
+///
+/// The underlying provider is always run with the context this provider is bound in, even when the
+/// service is first resolved from a sub-context, so the shared value never borrows from a
+/// sub-context that may be dropped before it:
+/// ```
+/// # use dfdi::{provider_fn, Context, Lifetime, Service, Shared};
+/// # use std::sync::Arc;
+/// #[derive(Service)]
+/// struct Config(String);
+///
+/// #[derive(Service)]
+/// struct Client<'cx>(&'cx Config);
+///
+/// let mut cx = Context::new();
+/// cx.bind_with_lifetime(
+///     Lifetime::Scoped,
+///     provider_fn::<Config>(|_cx, _arg| Config("https://example.com".to_string())),
+/// );
+/// cx.bind_with::<Arc<Client>>(Shared::new_fn(|cx, _arg| Client(cx.resolve::<&Config>())));
+///
+/// let request = cx.scoped();
+/// assert_eq!(request.resolve::<Arc<Client>>().0 .0, "https://example.com");
+/// drop(request);
+///
+/// assert_eq!(cx.resolve::<Arc<Client>>().0 .0, "https://example.com");
+/// ```
+pub struct Shared<'cx, S, P>
+where
+    S: Service + ?Sized,
+    P: Provider<'cx, S>,
+{
+    provider: P,
+    cache: OnceCell<Arc<S::Output<'cx>>>,
+}
+
+impl<'cx, S, P> Shared<'cx, S, P>
+where
+    S: Service + ?Sized,
+    P: Provider<'cx, S>,
+{
+    /// Create a new shared provider
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider,
+            cache: OnceCell::new(),
+        }
+    }
+}
+
+impl<'cx, S, F> Shared<'cx, S, F>
+where
+    S: Service + ?Sized,
+    F: Fn(&'cx Context, S::Argument<'_>) -> S::Output<'cx> + Send + Sync + 'cx,
+{
+    /// Equivelant to calling [`Shared::new`] with a provider wrapped in a
+    /// [`provider_fn`](crate::provider_fn) type hint
+    #[inline(always)]
+    pub fn new_fn(provider: F) -> Self {
+        Self::new(provider)
+    }
+}
+
+impl<'cx, S, P> Provider<'cx, Arc<S>> for Shared<'cx, S, P>
+where
+    S: Service + ?Sized,
+    S::Output<'cx>: Send + Sync,
+    P: Provider<'cx, S>,
+{
+    fn provide(&'cx self, cx: &'cx Context, arg: S::Argument<'_>) -> Arc<S::Output<'cx>> {
+        self.cache
+            .get_or_init(|| Arc::new(self.provider.provide(cx, arg)))
+            .clone()
+    }
+
+    fn try_provide(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'_>,
+    ) -> Result<Arc<S::Output<'cx>>, Box<dyn Error + Send + Sync>> {
+        self.cache
+            .get_or_try_init(|| self.provider.try_provide(cx, arg).map(Arc::new))
+            .cloned()
+    }
+
+    #[inline(always)]
+    fn dependencies(&self) -> Vec<Dependency> {
+        self.provider.dependencies()
+    }
+    #[inline(always)]
+    fn is_singleton(&self) -> bool {
+        true
+    }
+}
+
+impl<'cx, S, P> Default for Shared<'cx, S, P>
+where
+    S: Service + ?Sized,
+    P: Provider<'cx, S> + Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new(P::default())
+    }
+}
@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use dfdi_core::{Context, Provider, Service};
+
+/// Shared service
+///
+/// A provider that returns a shared pointer to the same value on every call
+pub struct SharedService<'cx, S: Service + ?Sized>(pub Arc<S::Output<'cx>>);
+
+impl<'cx, S: Service> SharedService<'cx, S> {
+    /// Create a new shared service
+    #[inline(always)]
+    pub fn new(value: S::Output<'cx>) -> Self {
+        Self(Arc::new(value))
+    }
+}
+
+impl<'cx, S> Provider<'cx, Arc<S>> for SharedService<'cx, S>
+where
+    S: Service + ?Sized,
+    S::Output<'cx>: Send + Sync,
+{
+    #[inline(always)]
+    fn provide(&'cx self, _cx: &'cx Context, _arg: S::Argument<'_>) -> Arc<S::Output<'cx>> {
+        self.0.clone()
+    }
+}
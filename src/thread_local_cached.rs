@@ -0,0 +1,109 @@
+use std::error::Error;
+
+use thread_local::ThreadLocal;
+
+use dfdi_core::{Context, Dependency, Provider, Service};
+
+/// Thread-local cached provider
+///
+/// A provider that calls the underlying provider on the first call from each thread and returns
+/// the result of that on all calls from the same thread. All of the values are dropped along with
+/// the provider.
+///
+/// Unlike [`Cached`](crate::Cached), the values only need to be [`Send`], which makes this provider
+/// suitable for per-thread state such as random number generators and buffers:
+/// ```
+/// # use dfdi::{Context, Service, ThreadLocalCached};
+/// # use std::cell::Cell;
+/// #[derive(Service)]
+/// struct Counter(Cell<u32>);
+///
+/// let mut cx = Context::new();
+/// cx.bind_with::<&Counter>(ThreadLocalCached::new_fn(|_cx, _arg| Counter(Cell::new(0))));
+///
+/// let counter = cx.resolve::<&Counter>();
+/// counter.0.set(counter.0.get() + 1);
+/// assert_eq!(cx.resolve::<&Counter>().0.get(), 1);
+///
+/// std::thread::scope(|s| {
+///     s.spawn(|| assert_eq!(cx.resolve::<&Counter>().0.get(), 0));
+/// });
+/// ```
+pub struct ThreadLocalCached<'cx, S, P>
+where
+    S: Service + ?Sized,
+    S::Output<'cx>: Send,
+    P: Provider<'cx, S>,
+{
+    provider: P,
+    cache: ThreadLocal<S::Output<'cx>>,
+}
+
+impl<'cx, S, P> ThreadLocalCached<'cx, S, P>
+where
+    S: Service + ?Sized,
+    S::Output<'cx>: Send,
+    P: Provider<'cx, S>,
+{
+    /// Create a new thread-local cached provider
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider,
+            cache: ThreadLocal::new(),
+        }
+    }
+}
+
+impl<'cx, S, F> ThreadLocalCached<'cx, S, F>
+where
+    S: Service + ?Sized,
+    S::Output<'cx>: Send,
+    F: Fn(&'cx Context, S::Argument<'_>) -> S::Output<'cx> + Send + Sync + 'cx,
+{
+    /// Equivelant to calling [`ThreadLocalCached::new`] with a provider wrapped in a
+    /// [`provider_fn`](crate::provider_fn) type hint
+    #[inline(always)]
+    pub fn new_fn(provider: F) -> Self {
+        Self::new(provider)
+    }
+}
+
+impl<'cx, S, P> Provider<'cx, &'static S> for ThreadLocalCached<'cx, S, P>
+where
+    S: Service + ?Sized,
+    S::Output<'cx>: Send,
+    P: Provider<'cx, S>,
+{
+    fn provide(&'cx self, cx: &'cx Context, arg: S::Argument<'_>) -> &'cx S::Output<'cx> {
+        self.cache.get_or(|| self.provider.provide(cx, arg))
+    }
+
+    fn try_provide(
+        &'cx self,
+        cx: &'cx Context,
+        arg: S::Argument<'_>,
+    ) -> Result<&'cx S::Output<'cx>, Box<dyn Error + Send + Sync>> {
+        self.cache.get_or_try(|| self.provider.try_provide(cx, arg))
+    }
+
+    #[inline(always)]
+    fn dependencies(&self) -> Vec<Dependency> {
+        self.provider.dependencies()
+    }
+    #[inline(always)]
+    fn is_singleton(&self) -> bool {
+        true
+    }
+}
+
+impl<'cx, S, P> Default for ThreadLocalCached<'cx, S, P>
+where
+    S: Service + ?Sized,
+    S::Output<'cx>: Send,
+    P: Provider<'cx, S> + Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new(P::default())
+    }
+}